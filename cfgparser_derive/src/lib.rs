@@ -0,0 +1,284 @@
+//! cfgparser_derive
+//!
+//! Provides `#[derive(CfgSection)]`, which generates an implementation of
+//! `TryFrom<&SectionOwned>` for a struct with named fields, reading each
+//! field from the key of the same name. It is re-exported by cfgparser
+//! when the `derive` feature is enabled.
+//!
+//! ```
+//! use cfgparser::{CfgSection, ConfigOwned};
+//! use std::convert::TryFrom;
+//!
+//! #[derive(CfgSection)]
+//! struct OperatingSystem {
+//!     architecture: String,
+//!     #[cfgparser(rename = "type")]
+//!     kind: String,
+//!     #[cfgparser(one_of = ["32", "64"])]
+//!     bits: u8,
+//!     #[cfgparser(default = "Current", one_of = ["Current", "Legacy", "Deprecated"])]
+//!     status: String,
+//!     python_version: Option<f32>,
+//! }
+//!
+//! let contents = "[cent7_64]\narchitecture = linux_cent7_x86_64\ntype = LINUX\nbits = 64\n";
+//! let config: ConfigOwned = cfgparser::Config::parse_cfg_from_str(contents).unwrap().to_owned();
+//! let os = OperatingSystem::try_from(config.section("cent7_64").unwrap()).unwrap();
+//! assert_eq!(os.kind, "LINUX");
+//! assert_eq!(os.status, "Current");
+//! assert_eq!(os.python_version, None);
+//! ```
+//!
+//! Fields are converted via cfgparser::FromValue, and a field of type
+//! `Option<T>` may be missing. The following attributes may be placed on a
+//! field, within `#[cfgparser(...)]`:
+//!
+//! - `rename = "key"` reads the field from a key other than its name
+//! - `default = "text"` supplies the text of the value when the key is missing
+//! - `default` uses `Default::default()` when the key is missing
+//! - `env = "VAR"` reads the value from an environment variable, when set,
+//!   in preference to the key
+//! - `min = expr` and `max = expr` bound the converted value
+//! - `one_of = ["a", "b"]` restricts the text of the value
+//! - `validate = "path"` calls a `fn(&T) -> Result<(), String>`, whose error
+//!   describes the constraint broken
+//!
+//! Conversion does not stop at the first problem. Every missing, invalid or
+//! constrained field is reported, as a `Vec<cfgparser::Error>`.
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Expr, Fields, GenericArgument, Ident, LitStr, Path,
+    PathArguments, Token, Type,
+};
+
+/// Derive `TryFrom<&SectionOwned>` for a struct with named fields. See the
+/// crate documentation for the attributes supported.
+#[proc_macro_derive(CfgSection, attributes(cfgparser))]
+pub fn derive_cfg_section(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+// how a field is filled when its key is missing
+enum Fallback {
+    // with Default::default()
+    Trait,
+    // with the supplied text, converted as the value would be
+    Text(LitStr),
+}
+
+// a field of the struct, along with its attributes
+struct Field<'f> {
+    ident: &'f Ident,
+    ty: &'f Type,
+    // the type within an Option, when the field is optional
+    inner: Option<&'f Type>,
+    key: String,
+    env: Option<LitStr>,
+    default: Option<Fallback>,
+    min: Option<Expr>,
+    max: Option<Expr>,
+    one_of: Vec<LitStr>,
+    validate: Option<Path>,
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2, syn::Error> {
+    let named = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => named,
+            _ => return Err(unsupported(input)),
+        },
+        _ => return Err(unsupported(input)),
+    };
+
+    // gather every problem with the attributes before giving up
+    let mut fields = Vec::new();
+    let mut errors: Option<syn::Error> = None;
+    for field in named.named.iter() {
+        match parse_field(field) {
+            Ok(field) => fields.push(field),
+            Err(error) => match &mut errors {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error),
+            },
+        }
+    }
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let idents = fields.iter().map(|field| field.ident).collect::<Vec<_>>();
+    let reads = fields.iter().map(read_field);
+    Ok(quote! {
+        impl #impl_generics ::std::convert::TryFrom<&::cfgparser::SectionOwned>
+            for #name #ty_generics #where_clause
+        {
+            type Error = ::std::vec::Vec<::cfgparser::Error>;
+
+            fn try_from(
+                __cfgparser_section: &::cfgparser::SectionOwned,
+            ) -> ::std::result::Result<Self, Self::Error> {
+                let mut __cfgparser_errors = ::std::vec::Vec::new();
+                #(let #idents = #reads;)*
+                #[allow(unreachable_patterns)]
+                match (#(#idents,)*) {
+                    (#(::std::option::Option::Some(#idents),)*) => {
+                        ::std::result::Result::Ok(Self { #(#idents),* })
+                    }
+                    _ => ::std::result::Result::Err(__cfgparser_errors),
+                }
+            }
+        }
+    })
+}
+
+fn unsupported(input: &DeriveInput) -> syn::Error {
+    syn::Error::new(
+        input.ident.span(),
+        "CfgSection may only be derived for structs with named fields",
+    )
+}
+
+fn parse_field(field: &syn::Field) -> Result<Field<'_>, syn::Error> {
+    let ident = field.ident.as_ref().expect("named fields have idents");
+    let mut parsed = Field {
+        ident,
+        ty: &field.ty,
+        inner: option_inner(&field.ty),
+        key: ident.unraw().to_string(),
+        env: None,
+        default: None,
+        min: None,
+        max: None,
+        one_of: Vec::new(),
+        validate: None,
+    };
+    let mut errors: Option<syn::Error> = None;
+    for attr in field.attrs.iter() {
+        if !attr.path().is_ident("cfgparser") {
+            continue;
+        }
+        let result = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                parsed.key = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("env") {
+                parsed.env = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("default") {
+                parsed.default = Some(if meta.input.is_empty() || meta.input.peek(Token![,]) {
+                    Fallback::Trait
+                } else {
+                    Fallback::Text(meta.value()?.parse()?)
+                });
+            } else if meta.path.is_ident("min") {
+                parsed.min = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("max") {
+                parsed.max = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("one_of") {
+                let value = meta.value()?;
+                let content;
+                syn::bracketed!(content in value);
+                let allowed =
+                    content.parse_terminated(|input| input.parse::<LitStr>(), Token![,])?;
+                parsed.one_of.extend(allowed);
+            } else if meta.path.is_ident("validate") {
+                parsed.validate = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+            } else {
+                return Err(meta.error("unknown cfgparser attribute"));
+            }
+            Ok(())
+        });
+        if let Err(error) = result {
+            match &mut errors {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error),
+            }
+        }
+    }
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(parsed),
+    }
+}
+
+// the type within an Option, if the type is one
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// the expression reading a field, which yields None, having recorded why,
+// when the field could not be read
+fn read_field(field: &Field<'_>) -> TokenStream2 {
+    let key = &field.key;
+    let ty = field.inner.unwrap_or(field.ty);
+    let env = match &field.env {
+        Some(var) => quote!(::std::option::Option::Some(#var)),
+        None => quote!(::std::option::Option::None),
+    };
+    let default = match &field.default {
+        Some(Fallback::Text(text)) => quote!(::std::option::Option::Some(#text)),
+        _ => quote!(::std::option::Option::None),
+    };
+
+    let mut checks = Vec::new();
+    if let Some(min) = &field.min {
+        checks.push(quote! {
+            &|_: &str, value: &#ty| ::cfgparser::derive::at_least(value, #min)
+        });
+    }
+    if let Some(max) = &field.max {
+        checks.push(quote! {
+            &|_: &str, value: &#ty| ::cfgparser::derive::at_most(value, #max)
+        });
+    }
+    if !field.one_of.is_empty() {
+        let allowed = &field.one_of;
+        checks.push(quote! {
+            &|text: &str, _: &#ty| ::cfgparser::derive::one_of(text, &[#(#allowed),*])
+        });
+    }
+    if let Some(validate) = &field.validate {
+        checks.push(quote! {
+            &|_: &str, value: &#ty| #validate(value)
+        });
+    }
+
+    let args = quote! {
+        __cfgparser_section,
+        #key,
+        #env,
+        #default,
+        &[#(#checks),*],
+        &mut __cfgparser_errors,
+    };
+    match (field.inner, &field.default) {
+        (Some(_), _) => quote!(::cfgparser::derive::optional::<#ty>(#args)),
+        (None, Some(Fallback::Trait)) => quote! {
+            ::cfgparser::derive::optional::<#ty>(#args)
+                .map(::std::option::Option::unwrap_or_default)
+        },
+        (None, _) => quote!(::cfgparser::derive::required::<#ty>(#args)),
+    }
+}
@@ -0,0 +1,133 @@
+use cfgparser::{CfgSection, Config, ConfigOwned, Error, FromValue};
+use std::convert::TryFrom;
+
+#[derive(Debug, PartialEq)]
+enum Status {
+    Current,
+    Legacy,
+}
+
+impl FromValue for Status {
+    fn from_value(section: Option<&str>, key: &str, value: &str) -> Result<Self, Error> {
+        match value {
+            "Current" => Ok(Status::Current),
+            "Legacy" => Ok(Status::Legacy),
+            _ => Err(Error::InvalidValue {
+                section: section.map(str::to_string),
+                key: key.to_string(),
+                value: value.to_string(),
+                target: "Status".to_string(),
+            }),
+        }
+    }
+}
+
+fn power_of_two(value: &u8) -> Result<(), String> {
+    if !value.is_power_of_two() {
+        return Err("must be a power of two".to_string());
+    }
+    Ok(())
+}
+
+#[derive(Debug, CfgSection)]
+struct OperatingSystem {
+    architecture: String,
+    r#type: String,
+    #[cfgparser(min = 32, max = 64, validate = "power_of_two")]
+    bits: u8,
+    #[cfgparser(rename = "python_version")]
+    python: f32,
+    #[cfgparser(default = "Current")]
+    status: Status,
+    #[cfgparser(default)]
+    hosts: Vec<String>,
+    #[cfgparser(one_of = ["yes", "no"])]
+    secure: Option<bool>,
+}
+
+fn config(contents: &str) -> ConfigOwned {
+    Config::parse_cfg_from_str(contents).unwrap().to_owned()
+}
+
+#[test]
+fn given_valid_section_try_from_reads_every_field() {
+    let config = config(
+        "[cent7_64]\n\
+         architecture = linux_cent7_x86_64\n\
+         type = LINUX\n\
+         bits = 64\n\
+         python_version = 2.7\n\
+         hosts = lichost1,lichost2\n",
+    );
+    let os = OperatingSystem::try_from(config.section("cent7_64").unwrap()).unwrap();
+    assert_eq!(os.architecture, "linux_cent7_x86_64");
+    assert_eq!(os.r#type, "LINUX");
+    assert_eq!(os.bits, 64);
+    assert_eq!(os.python, 2.7);
+    assert_eq!(os.status, Status::Current);
+    assert_eq!(os.hosts, vec!["lichost1", "lichost2"]);
+    assert_eq!(os.secure, None);
+}
+
+#[test]
+fn given_defaulted_fields_missing_try_from_fills_them() {
+    let config = config(
+        "[cent6_64]\n\
+         architecture = linux_cent6_x86_64\n\
+         type = LINUX\n\
+         bits = 32\n\
+         python_version = 2.6\n\
+         status = Legacy\n\
+         secure = no\n",
+    );
+    let os = OperatingSystem::try_from(config.section("cent6_64").unwrap()).unwrap();
+    assert_eq!(os.status, Status::Legacy);
+    assert!(os.hosts.is_empty());
+    assert_eq!(os.secure, Some(false));
+}
+
+#[test]
+fn given_many_problems_try_from_reports_them_all() {
+    let config = config(
+        "[broken]\n\
+         type = LINUX\n\
+         bits = 65\n\
+         python_version = two\n\
+         status = Retired\n\
+         secure = on\n",
+    );
+    let errors = OperatingSystem::try_from(config.section("broken").unwrap()).unwrap_err();
+    let messages = errors.iter().map(Error::to_string).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "missing key 'architecture'",
+            "value '65' of key 'bits' must be at most 64",
+            "value '65' of key 'bits' must be a power of two",
+            "value 'two' of key 'python_version' is not a valid f32",
+            "value 'Retired' of key 'status' is not a valid Status",
+            "value 'on' of key 'secure' must be one of yes, no",
+        ]
+    );
+}
+
+#[derive(Debug, CfgSection)]
+struct Site {
+    #[cfgparser(env = "CFGPARSER_DERIVE_TEST_SITE_NAME", default = "local")]
+    name: String,
+    #[cfgparser(env = "CFGPARSER_DERIVE_TEST_SITE_UNSET")]
+    root: String,
+}
+
+#[test]
+fn given_env_attribute_try_from_prefers_the_variable() {
+    let config = config("[site]\nname = fromfile\nroot = /opt\n");
+    let section = config.section("site").unwrap();
+    assert_eq!(Site::try_from(section).unwrap().name, "fromfile");
+
+    std::env::set_var("CFGPARSER_DERIVE_TEST_SITE_NAME", "fromenv");
+    let site = Site::try_from(section).unwrap();
+    std::env::remove_var("CFGPARSER_DERIVE_TEST_SITE_NAME");
+    assert_eq!(site.name, "fromenv");
+    assert_eq!(site.root, "/opt");
+}
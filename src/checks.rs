@@ -0,0 +1,37 @@
+//! checks
+//!
+//! The constraints which may be placed upon a value, shared by Schema and
+//! the code generated by `#[derive(CfgSection)]`. Each check returns a
+//! description of the constraint when the value breaks it, which becomes the
+//! message of an Error::Constraint.
+use std::fmt::Display;
+
+/// Check that the value is no less than the minimum
+pub fn at_least<T>(value: &T, min: T) -> Result<(), String>
+where
+    T: PartialOrd + Display,
+{
+    if *value < min {
+        return Err(format!("must be at least {}", min));
+    }
+    Ok(())
+}
+
+/// Check that the value is no greater than the maximum
+pub fn at_most<T>(value: &T, max: T) -> Result<(), String>
+where
+    T: PartialOrd + Display,
+{
+    if *value > max {
+        return Err(format!("must be at most {}", max));
+    }
+    Ok(())
+}
+
+/// Check that the text of the value is one of those allowed
+pub fn one_of(text: &str, allowed: &[&str]) -> Result<(), String> {
+    if !allowed.contains(&text) {
+        return Err(format!("must be one of {}", allowed.join(", ")));
+    }
+    Ok(())
+}
@@ -1,31 +1,40 @@
-use crate::parser::parse_cfg_from_str;
+use crate::env::expand;
+use crate::parser::driver::{fold_key, parse_lines, parse_lines_recovering};
+use crate::section::remove_ordered;
+use crate::typed::{convert, split_list, to_bool};
+use crate::view::Items;
+use crate::Error;
+use crate::Interpolator;
+use crate::MergeStrategy;
+use crate::Origin;
+use crate::ParserOptions;
 use crate::Section;
 use crate::SectionOwned;
+use crate::SectionView;
+use crate::VariableSource;
+use std::borrow::Cow;
+use std::str::FromStr;
 
 #[cfg(not(feature = "ordered"))]
 use std::collections::HashMap;
 
 #[cfg(not(feature = "ordered"))]
-use std::collections::hash_map::Values;
+use std::collections::hash_map::{Iter, Values, ValuesMut};
 
 #[cfg(feature = "ordered")]
 use indexmap::IndexMap as HashMap;
 
 #[cfg(feature = "ordered")]
-use indexmap::map::Values;
+use indexmap::map::{Iter, Values, ValuesMut};
 
 /// Config structure
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct Config<'b> {
-    sections: HashMap<&'b str, Section<'b>>,
-}
-
-impl<'b> Default for Config<'b> {
-    fn default() -> Config<'b> {
-        Self {
-            sections: HashMap::new(),
-        }
-    }
+    sections: HashMap<Cow<'b, str>, Section<'b>>,
+    global_name: Option<String>,
+    default_name: Option<String>,
+    // keys were folded to lower case when parsing, so lookups fold them too
+    fold_case: bool,
 }
 
 impl<'b> Config<'b> {
@@ -34,26 +43,58 @@ impl<'b> Config<'b> {
         Self::default()
     }
     /// Insert a section into the config
-    pub fn insert(&mut self, name: &'b str, section: Section<'b>) -> bool {
-        self.sections.insert(name, section).is_none()
+    pub fn insert<N>(&mut self, name: N, section: Section<'b>) -> bool
+    where
+        N: Into<Cow<'b, str>>,
+    {
+        self.sections.insert(name.into(), section).is_none()
     }
     /// Create an instance of ConfigOwned from self. ConfigOwned, as it sounds,
     /// owns its data, whereas Config is a view onto data
     /// that has been passed into it.
     pub fn to_owned(&self) -> ConfigOwned {
         let mut config = ConfigOwned::new();
-        for (key, section) in self.sections.iter() {
-            config.insert(*key, section);
+        for section in self.sections.values() {
+            let (name, section) = section.to_owned();
+            config.insert(name, section);
         }
+        config.global_name = self.global_name.clone();
+        config.default_name = self.default_name.clone();
+        config.fold_case = self.fold_case;
         config
     }
 
     /// Retrieve an iterator over sections in the config
-    pub fn sections(&self) -> Values<&str, Section> {
+    pub fn sections(&self) -> Values<'_, Cow<'b, str>, Section<'b>> {
         self.sections.values()
     }
 
+    /// Retrieve the implicit section holding any keys which appear before
+    /// the first section header. See ParserOptions::global_section.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, ParserOptions, Section};
+    ///
+    /// let contents = "version = 3\n\n[cent7_64]\nbits = 64\n";
+    /// let opts = ParserOptions::new().global_section("root");
+    /// let config = Config::parse_with(contents, &opts).unwrap();
+    ///
+    /// let mut expected = Section::new("root");
+    /// expected.insert("version", "3");
+    /// assert_eq!(config.globals(), Some(&expected));
+    /// assert_eq!(config.section("root"), Some(&expected));
+    /// ```
+    pub fn globals(&self) -> Option<&Section<'b>> {
+        self.global_name
+            .as_ref()
+            .and_then(|name| self.sections.get(name.as_str()))
+    }
+
     /// Given a &str representing a cfg, parse it into a Config instance.
+    /// If the cfg is malformed, the returned Error describes the first
+    /// problem found, along with its location.
     ///
     /// # Example
     ///
@@ -80,39 +121,271 @@ impl<'b> Config<'b> {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn parse_cfg_from_str(input: &'b str) -> Result<Self, String> {
-        match parse_cfg_from_str(input) {
-            Ok((_, sections)) => {
-                let mut cfg = Self::new();
-                for section in sections.into_iter() {
-                    cfg.insert(section.name(), section);
-                }
-                Ok(cfg)
-            }
-            Err(e) => Err(format!("{:?}", e)),
+    pub fn parse_cfg_from_str(input: &'b str) -> Result<Self, Error> {
+        Self::parse_with(input, &ParserOptions::default())
+    }
+
+    /// Given a &str representing a cfg, parse it into a Config instance,
+    /// using the supplied ParserOptions to control the grammar.
+    pub fn parse_with(input: &'b str, opts: &ParserOptions) -> Result<Self, Error> {
+        let mut cfg = Self::new();
+        for section in parse_lines(input, opts)?.into_iter() {
+            cfg.insert(section.cow_name(), section);
         }
+        cfg.global_name = opts.global_section.clone();
+        cfg.default_name = opts.default_section.clone();
+        cfg.fold_case = opts.fold_case;
+        Ok(cfg)
+    }
+
+    /// Given a &str representing a cfg, parse as much of it as possible,
+    /// returning the partial Config along with every problem encountered.
+    /// After a problem, parsing resumes on the next line. Lines following a
+    /// header which could not be used are skipped until the next header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, Diagnostic};
+    ///
+    /// let contents = r#"
+    /// [playa]
+    /// name = Playa Vista
+    /// short_name = ddpv
+    ///
+    /// [portland
+    /// name = Portland
+    ///
+    /// [montreal]
+    /// name = Montreal
+    /// "#;
+    ///
+    /// let (config, errors) = Config::parse_cfg_recovering(contents);
+    /// assert_eq!(errors.len(), 2);
+    /// for error in errors.iter() {
+    ///     eprintln!("{}", Diagnostic::from(error));
+    /// }
+    /// assert!(config.get("playa").is_some());
+    /// assert!(config.get("montreal").is_some());
+    /// ```
+    pub fn parse_cfg_recovering(input: &'b str) -> (Self, Vec<Error>) {
+        Self::parse_recovering_with(input, &ParserOptions::default())
+    }
+
+    /// Given a &str representing a cfg, parse as much of it as possible,
+    /// using the supplied ParserOptions to control the grammar. See
+    /// parse_cfg_recovering.
+    pub fn parse_recovering_with(input: &'b str, opts: &ParserOptions) -> (Self, Vec<Error>) {
+        let (sections, errors) = parse_lines_recovering(input, opts);
+        let mut cfg = Self::new();
+        for section in sections.into_iter() {
+            cfg.insert(section.cow_name(), section);
+        }
+        cfg.global_name = opts.global_section.clone();
+        cfg.default_name = opts.default_section.clone();
+        cfg.fold_case = opts.fold_case;
+        (cfg, errors)
+    }
+
+    /// Retrieve a section given its name, as it was parsed. The section
+    /// holds only the keys it defines itself; use get to include the keys it
+    /// inherits.
+    pub fn section(&self, name: &str) -> Option<&Section<'b>> {
+        self.sections.get(name)
+    }
+
+    /// Retrieve where the value of a key within a section was defined. The
+    /// key is looked up as with get_value.
+    pub fn origin(&self, section: &str, key: &str) -> Option<&Origin> {
+        let item = self.get(section)?.item(key)?;
+        self.sections.get(item.section)?.origin(item.key)
+    }
+
+    // mutable access to every section in the config
+    pub(crate) fn sections_mut(&mut self) -> ValuesMut<'_, Cow<'b, str>, Section<'b>> {
+        self.sections.values_mut()
+    }
+
+    /// Retrieve the section holding defaults for every other section. See
+    /// ParserOptions::default_section.
+    pub fn defaults(&self) -> Option<&Section<'b>> {
+        self.default_name
+            .as_ref()
+            .and_then(|name| self.sections.get(name.as_str()))
+    }
+
+    /// Retrieve a section given its name. The section is resolved, falling
+    /// back to the section's parent, its parent's parent and so on, and then
+    /// to the defaults section, for any key the section does not define.
+    /// Iterating over it marks inherited keys apart from the section's own.
+    /// Use section to retrieve the section as it was parsed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, Dialect, ParserOptions};
+    ///
+    /// let contents = "[DEFAULT]\nbits = 64\n\n[cent7_64]\nversion = 7\n";
+    /// let config = Config::parse_with(contents, &ParserOptions::from(Dialect::Python)).unwrap();
+    ///
+    /// let resolved = config.get("cent7_64").unwrap();
+    /// assert_eq!(resolved.get("bits"), Some("64"));
+    /// assert_eq!(resolved.get("version"), Some("7"));
+    /// let local = resolved.iter().filter(|item| !item.inherited);
+    /// assert_eq!(local.map(|item| item.key).collect::<Vec<_>>(), vec!["version"]);
+    ///
+    /// let parsed = config.section("cent7_64").unwrap();
+    /// assert_eq!(parsed.get("bits"), None);
+    /// ```
+    pub fn get(&self, section: &str) -> Option<SectionView<'_>> {
+        let lookup = |name: &str| {
+            self.sections
+                .get_key_value(name)
+                .map(|(name, section)| (name.as_ref(), section as &dyn Items))
+        };
+        SectionView::resolve(
+            section,
+            lookup,
+            self.default_name.as_deref(),
+            self.fold_case,
+        )
+    }
+
+    /// Retrieve the value of a key within a section, falling back to the
+    /// section's ancestors and the defaults section when the section does not
+    /// define it. When the config was parsed with ParserOptions::fold_case,
+    /// the key is folded to lower case first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, Dialect, ParserOptions};
+    ///
+    /// let contents = "[DEFAULT]\nstatus = Current\n\n[cent6_64]\nstatus = Legacy\n\n[cent7_64]\n";
+    /// let config = Config::parse_with(contents, &ParserOptions::from(Dialect::Python)).unwrap();
+    /// assert_eq!(config.get_value("cent6_64", "status"), Some("Legacy"));
+    /// assert_eq!(config.get_value("cent7_64", "status"), Some("Current"));
+    /// assert_eq!(config.get_value("cent7_64", "Status"), Some("Current"));
+    /// assert_eq!(config.get_value("cent8_64", "status"), None);
+    /// ```
+    pub fn get_value(&self, section: &str, key: &str) -> Option<&str> {
+        self.get(section)?.get(key)
     }
 
-    /// Retrieve a section given its name
+    /// Retrieve the value of a key within a section, with any references to
+    /// other keys expanded by the supplied Interpolator. References are
+    /// looked up as with get_value. Returns Ok(None) if the key is not found.
+    pub fn get_interpolated(
+        &self,
+        section: &str,
+        key: &str,
+        interpolator: &Interpolator,
+    ) -> Result<Option<String>, Error> {
+        interpolator.interpolate(|s, k| self.get_value(s, k), section, key)
+    }
+
+    /// Retrieve the value of a key within a section, with any environment
+    /// variables, written as `$VAR`, `${VAR}` or `${VAR:-default}`, expanded
+    /// using the supplied source. The key is looked up as with get_value.
+    /// Returns Ok(None) if the key is not found.
     ///
     /// # Example
     ///
-    pub fn get(&self, section: &str) -> Option<&Section> {
-        self.sections.get(section)
+    /// ```
+    /// use cfgparser::{Config, Error};
+    /// use std::collections::HashMap;
+    ///
+    /// let contents = "[site]\nroot = $STUDIO_ROOT/pipeline\ntmp = ${TMPDIR:-/tmp}\n";
+    /// let config = Config::parse_cfg_from_str(contents).unwrap();
+    ///
+    /// let mut env = HashMap::new();
+    /// env.insert("STUDIO_ROOT", "/studio");
+    /// let root = config.get_expanded("site", "root", &env).unwrap();
+    /// assert_eq!(root, Some("/studio/pipeline".to_string()));
+    /// let tmp = config.get_expanded("site", "tmp", &env).unwrap();
+    /// assert_eq!(tmp, Some("/tmp".to_string()));
+    ///
+    /// let result = config.get_expanded("site", "root", &HashMap::<&str, &str>::new());
+    /// assert!(matches!(result, Err(Error::UndefinedVariable { .. })));
+    /// ```
+    pub fn get_expanded<V>(
+        &self,
+        section: &str,
+        key: &str,
+        source: &V,
+    ) -> Result<Option<String>, Error>
+    where
+        V: VariableSource,
+    {
+        self.get_value(section, key)
+            .map(|value| expand(source, section, key, value))
+            .transpose()
+    }
+
+    /// Retrieve the value of a key within a section, converted to any type
+    /// implementing FromStr. The key is looked up as with get_value. Returns
+    /// Ok(None) if the key is not found, and Error::InvalidValue, naming the
+    /// section, key, value and type, if the value does not parse.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, Error};
+    ///
+    /// let contents = "[cent7_64]\nbits = 64\npython_version = 2.7\nstatus = Current\n";
+    /// let config = Config::parse_cfg_from_str(contents).unwrap();
+    /// assert_eq!(config.get_int("cent7_64", "bits").unwrap(), Some(64));
+    /// assert_eq!(config.get_float("cent7_64", "python_version").unwrap(), Some(2.7));
+    /// assert_eq!(config.get_as::<u8>("cent7_64", "bits").unwrap(), Some(64));
+    ///
+    /// let error = config.get_bool("cent7_64", "status").unwrap_err();
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "value 'Current' of key 'status' in section 'cent7_64' is not a valid bool"
+    /// );
+    /// ```
+    pub fn get_as<T>(&self, section: &str, key: &str) -> Result<Option<T>, Error>
+    where
+        T: FromStr,
+    {
+        self.get_value(section, key)
+            .map(|value| convert(Some(section), key, value))
+            .transpose()
+    }
+
+    /// Retrieve the value of a key within a section as a boolean. Accepts
+    /// yes/no, on/off, true/false and 1/0, regardless of case. See get_as.
+    pub fn get_bool(&self, section: &str, key: &str) -> Result<Option<bool>, Error> {
+        self.get_value(section, key)
+            .map(|value| to_bool(Some(section), key, value))
+            .transpose()
+    }
+
+    /// Retrieve the value of a key within a section as an integer. See get_as.
+    pub fn get_int(&self, section: &str, key: &str) -> Result<Option<i64>, Error> {
+        self.get_as(section, key)
+    }
+
+    /// Retrieve the value of a key within a section as a floating point
+    /// number. See get_as.
+    pub fn get_float(&self, section: &str, key: &str) -> Result<Option<f64>, Error> {
+        self.get_as(section, key)
+    }
+
+    /// Retrieve the value of a key within a section as a list. See
+    /// Section::get_list.
+    pub fn get_list(&self, section: &str, key: &str, separator: &str) -> Option<Vec<&str>> {
+        self.get_value(section, key)
+            .map(|value| split_list(value, separator))
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct ConfigOwned {
     sections: HashMap<String, SectionOwned>,
-}
-
-impl Default for ConfigOwned {
-    fn default() -> Self {
-        Self {
-            sections: HashMap::new(),
-        }
-    }
+    global_name: Option<String>,
+    default_name: Option<String>,
+    fold_case: bool,
 }
 
 impl ConfigOwned {
@@ -120,20 +393,356 @@ impl ConfigOwned {
     pub fn new() -> Self {
         Self::default()
     }
-    /// Insert a section into the config
-    pub fn insert(&mut self, section_name: &str, section: &Section) -> bool {
-        let (name, section) = section.to_owned();
-        // todo - add custom error
-        assert_eq!(name, section_name);
-        self.sections.insert(name.to_string(), section).is_none()
+    /// Insert a section into the config, replacing any section of the same
+    /// name. Returns true if there was no such section.
+    pub fn insert<N>(&mut self, name: N, mut section: SectionOwned) -> bool
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+        section.set_name(name.as_str());
+        self.sections.insert(name, section).is_none()
+    }
+
+    /// Set the value of a key within a section, creating the section if
+    /// necessary. Returns the previous value, if there was one. When the
+    /// config was parsed with ParserOptions::fold_case, the key is folded to
+    /// lower case first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::ConfigOwned;
+    ///
+    /// let mut config = ConfigOwned::new();
+    /// config.set("cent7_64", "bits", "64");
+    /// assert_eq!(config.set("cent7_64", "bits", "32"), Some("64".to_string()));
+    /// assert_eq!(config.remove("cent7_64", "bits"), Some("32".to_string()));
+    /// assert!(config.get("cent7_64").is_some());
+    /// ```
+    pub fn set<N, K, V>(&mut self, section: N, key: K, value: V) -> Option<String>
+    where
+        N: Into<String>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let key = key.into();
+        let key = match self.fold_key(&key) {
+            Cow::Owned(folded) => folded,
+            Cow::Borrowed(_) => key,
+        };
+        self.section_mut_or_insert(section).insert(key, value)
+    }
+
+    /// Remove a key from a section, returning its value if it was present.
+    /// The section is kept, even once it has no keys. The key is folded as
+    /// with set.
+    pub fn remove(&mut self, section: &str, key: &str) -> Option<String> {
+        let key = self.fold_key(key);
+        self.get_mut(section)?.remove(&key)
+    }
+
+    // fold the key to lower case if the config was parsed with fold_case
+    pub(crate) fn fold_key<'k>(&self, key: &'k str) -> Cow<'k, str> {
+        fold_key(key, self.fold_case)
+    }
+
+    /// Retrieve a mutable reference to a section
+    pub fn get_mut(&mut self, section: &str) -> Option<&mut SectionOwned> {
+        self.sections.get_mut(section)
+    }
+
+    /// Retrieve a mutable reference to a section, inserting an empty section
+    /// if there is none of that name
+    pub fn section_mut_or_insert<N>(&mut self, name: N) -> &mut SectionOwned
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+        self.sections.entry(name.clone()).or_insert_with(|| {
+            let mut section = SectionOwned::new();
+            section.set_name(name);
+            section
+        })
+    }
+
+    /// Remove a section, returning it if it was present
+    pub fn remove_section(&mut self, name: &str) -> Option<SectionOwned> {
+        remove_ordered(&mut self.sections, name)
+    }
+
+    /// Rename a section, keeping its place in the config. Sections which
+    /// inherit from it are updated to inherit from the new name, and it stays
+    /// the globals or defaults section if it was one. Returns false,
+    /// leaving the config untouched, if there is no section named `from`, or
+    /// there is already one named `to`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{ConfigOwned, SectionOwned};
+    ///
+    /// let mut config = ConfigOwned::new();
+    /// config.set("cent6_64", "bits", "64");
+    /// let mut cent7 = SectionOwned::new();
+    /// cent7.set_parent("cent6_64");
+    /// config.insert("cent7_64", cent7);
+    ///
+    /// assert!(config.rename_section("cent6_64", "centos6"));
+    /// assert_eq!(config.section("cent7_64").unwrap().parent(), Some("centos6"));
+    /// assert_eq!(config.get_value("cent7_64", "bits"), Some("64"));
+    /// ```
+    pub fn rename_section(&mut self, from: &str, to: &str) -> bool {
+        if !self.sections.contains_key(from) || self.sections.contains_key(to) {
+            return false;
+        }
+        self.sections = std::mem::take(&mut self.sections)
+            .into_iter()
+            .map(|(name, mut section)| {
+                if section.parent() == Some(from) {
+                    section.set_parent(to);
+                }
+                let name = if name == from {
+                    section.set_name(to);
+                    to.to_string()
+                } else {
+                    name
+                };
+                (name, section)
+            })
+            .collect();
+        for name in [&mut self.global_name, &mut self.default_name] {
+            if name.as_deref() == Some(from) {
+                *name = Some(to.to_string());
+            }
+        }
+        true
+    }
+
+    /// Keep only the sections for which the predicate returns true
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&str, &mut SectionOwned) -> bool,
+    {
+        self.sections.retain(|name, section| keep(name, section));
+    }
+
+    /// Remove every section from the config
+    pub fn clear(&mut self) {
+        self.sections.clear();
+    }
+
+    /// Retrieve an iterator over the names and sections in the config
+    pub fn iter(&self) -> Iter<'_, String, SectionOwned> {
+        self.sections.iter()
+    }
+
+    /// Retrieve a section given its name, as it was parsed or built. See
+    /// Config::section.
+    pub fn section<I>(&self, name: I) -> Option<&SectionOwned>
+    where
+        I: AsRef<str>,
+    {
+        self.sections.get(name.as_ref())
+    }
+
+    /// Merge a later config into self. Sections and keys are combined as
+    /// directed by the strategy, after applying any tombstones in the later
+    /// config: a section declared as `[!name]` replaces the section of the
+    /// same name, or removes it if it has no keys, and a `!key` line removes
+    /// the key. See ParserOptions::tombstones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, MergeStrategy, ParserOptions};
+    ///
+    /// let opts = ParserOptions::new().tombstones(true);
+    /// let site = Config::parse_with("[cent6_64]\nbits = 64\n[cent7_64]\nbits = 64\n", &opts).unwrap();
+    /// let user = Config::parse_with("[!cent6_64]\n[cent7_64]\nbits = 32\n", &opts).unwrap();
+    ///
+    /// let mut config = site.to_owned();
+    /// config.merge(user.to_owned(), MergeStrategy::Override);
+    /// assert!(config.get("cent6_64").is_none());
+    /// assert_eq!(config.get_value("cent7_64", "bits"), Some("32"));
+    /// ```
+    pub fn merge(&mut self, other: ConfigOwned, strategy: MergeStrategy) {
+        for (name, mut section) in other.sections.into_iter() {
+            let replace = section.replaces() || strategy == MergeStrategy::ReplaceSections;
+            match self.sections.get_mut(&name) {
+                Some(existing) if !replace => existing.merge(section, strategy),
+                _ if section.replaces() && section.is_empty() => {
+                    remove_ordered(&mut self.sections, &name);
+                }
+                _ => {
+                    section.clear_tombstones();
+                    section.set_name(name.as_str());
+                    self.sections.insert(name, section);
+                }
+            }
+        }
+        self.global_name = other.global_name.or_else(|| self.global_name.take());
+        self.default_name = other.default_name.or_else(|| self.default_name.take());
+        self.fold_case |= other.fold_case;
+    }
+
+    /// Retrieve the implicit section holding any keys which appear before
+    /// the first section header. See ParserOptions::global_section.
+    pub fn globals(&self) -> Option<&SectionOwned> {
+        self.global_name
+            .as_ref()
+            .and_then(|name| self.sections.get(name))
+    }
+
+    /// Retrieve where the value of a key within a section was defined. The
+    /// key is looked up as with get_value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::from_path;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut cfgpath = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    /// cfgpath.push("example_data");
+    /// cfgpath.push("operating_systems.cfg");
+    /// let config = from_path(&cfgpath)?;
+    ///
+    /// let origin = config.origin("cent7_64", "bits").unwrap();
+    /// assert_eq!(origin.path.as_ref(), Some(&cfgpath));
+    /// assert_eq!(origin.line, 15);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn origin<I>(&self, section: I, key: &str) -> Option<&Origin>
+    where
+        I: AsRef<str>,
+    {
+        let item = self.get(section)?.item(key)?;
+        self.sections.get(item.section)?.origin(item.key)
+    }
+
+    /// Retrieve the section holding defaults for every other section. See
+    /// ParserOptions::default_section.
+    pub fn defaults(&self) -> Option<&SectionOwned> {
+        self.default_name
+            .as_ref()
+            .and_then(|name| self.sections.get(name))
+    }
+
+    /// Retrieve a section given its name, resolved so that it falls back to
+    /// the section's ancestors, and then to the defaults section, for any key
+    /// the section does not define. See Config::get.
+    pub fn get<I>(&self, section: I) -> Option<SectionView<'_>>
+    where
+        I: AsRef<str>,
+    {
+        let lookup = |name: &str| {
+            self.sections
+                .get_key_value(name)
+                .map(|(name, section)| (name.as_str(), section as &dyn Items))
+        };
+        SectionView::resolve(
+            section.as_ref(),
+            lookup,
+            self.default_name.as_deref(),
+            self.fold_case,
+        )
+    }
+
+    /// Retrieve the value of a key within a section, falling back to the
+    /// section's ancestors and the defaults section when the section does not
+    /// define it.
+    pub fn get_value<I>(&self, section: I, key: &str) -> Option<&str>
+    where
+        I: AsRef<str>,
+    {
+        self.get(section)?.get(key)
+    }
+
+    /// Retrieve the value of a key within a section, with any references to
+    /// other keys expanded by the supplied Interpolator. See
+    /// Config::get_interpolated.
+    pub fn get_interpolated<I>(
+        &self,
+        section: I,
+        key: &str,
+        interpolator: &Interpolator,
+    ) -> Result<Option<String>, Error>
+    where
+        I: AsRef<str>,
+    {
+        interpolator.interpolate(|s, k| self.get_value(s, k), section.as_ref(), key)
+    }
+
+    /// Retrieve the value of a key within a section, with any environment
+    /// variables expanded using the supplied source. See Config::get_expanded.
+    pub fn get_expanded<I, V>(
+        &self,
+        section: I,
+        key: &str,
+        source: &V,
+    ) -> Result<Option<String>, Error>
+    where
+        I: AsRef<str>,
+        V: VariableSource,
+    {
+        let section = section.as_ref();
+        self.get_value(section, key)
+            .map(|value| expand(source, section, key, value))
+            .transpose()
+    }
+
+    /// Retrieve the value of a key within a section, converted to any type
+    /// implementing FromStr. See Config::get_as.
+    pub fn get_as<I, T>(&self, section: I, key: &str) -> Result<Option<T>, Error>
+    where
+        I: AsRef<str>,
+        T: FromStr,
+    {
+        let section = section.as_ref();
+        self.get_value(section, key)
+            .map(|value| convert(Some(section), key, value))
+            .transpose()
+    }
+
+    /// Retrieve the value of a key within a section as a boolean. See
+    /// Config::get_bool.
+    pub fn get_bool<I>(&self, section: I, key: &str) -> Result<Option<bool>, Error>
+    where
+        I: AsRef<str>,
+    {
+        let section = section.as_ref();
+        self.get_value(section, key)
+            .map(|value| to_bool(Some(section), key, value))
+            .transpose()
+    }
+
+    /// Retrieve the value of a key within a section as an integer. See
+    /// Config::get_as.
+    pub fn get_int<I>(&self, section: I, key: &str) -> Result<Option<i64>, Error>
+    where
+        I: AsRef<str>,
+    {
+        self.get_as(section, key)
+    }
+
+    /// Retrieve the value of a key within a section as a floating point
+    /// number. See Config::get_as.
+    pub fn get_float<I>(&self, section: I, key: &str) -> Result<Option<f64>, Error>
+    where
+        I: AsRef<str>,
+    {
+        self.get_as(section, key)
     }
 
-    /// Retrieve a section
-    pub fn get<I>(&self, section: I) -> Option<&SectionOwned>
+    /// Retrieve the value of a key within a section as a list. See
+    /// Section::get_list.
+    pub fn get_list<I>(&self, section: I, key: &str, separator: &str) -> Option<Vec<&str>>
     where
         I: AsRef<str>,
     {
-        self.sections.get(section.as_ref())
+        self.get_value(section, key)
+            .map(|value| split_list(value, separator))
     }
 }
 
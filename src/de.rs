@@ -0,0 +1,560 @@
+//! de
+//!
+//! This module, available with the `serde` feature, deserializes a cfg
+//! directly into Rust types. A config maps onto a struct, or a map, whose
+//! fields are sections, and each section maps onto a struct, or a map, whose
+//! fields are keys:
+//!
+//! ```
+//! use serde::Deserialize;
+//! use std::collections::HashMap;
+//!
+//! #[derive(Deserialize)]
+//! struct OperatingSystem {
+//!     bits: u8,
+//!     python_version: f32,
+//!     legacy: Option<String>,
+//! }
+//!
+//! let contents = "[cent7_64]\nbits = 64\npython_version = 2.7\n";
+//! let systems: HashMap<String, OperatingSystem> = cfgparser::from_str(contents).unwrap();
+//! assert_eq!(systems["cent7_64"].bits, 64);
+//! assert_eq!(systems["cent7_64"].legacy, None);
+//! ```
+//!
+//! Values are coerced into numbers and booleans using the same rules as the
+//! typed getters, such as Section::get_int and Section::get_bool. Unit enum
+//! variants are matched by name, an empty value deserializes as None, and a
+//! sequence is read from a comma separated list, or one separated by
+//! ParserOptions::list_separator. Sections see the keys they
+//! inherit, as with Config::get, and the defaults section is not presented
+//! as a section of its own. Errors name the section and key at fault.
+use crate::error::Error;
+use crate::typed::{convert, split_list, to_bool};
+use crate::{Config, ConfigOwned, ParserOptions, SectionOwned, SectionView, ViewItem};
+use serde::de::value::{SeqDeserializer, StrDeserializer};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+impl de::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Error::Serde {
+            section: None,
+            key: None,
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// Deserialize an instance of T from a cfg, parsed with the default
+/// ParserOptions
+pub fn from_str<T>(input: &str) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    from_str_with(input, &ParserOptions::default())
+}
+
+/// Deserialize an instance of T from a cfg, parsed with the supplied
+/// ParserOptions, whose list separator splits sequences
+///
+/// ```
+/// use cfgparser::ParserOptions;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Site {
+///     hosts: Vec<String>,
+/// }
+///
+/// let opts = ParserOptions::new().list_separator(":");
+/// let sites: std::collections::HashMap<String, Site> =
+///     cfgparser::de::from_str_with("[site]\nhosts = lichost1:lichost2\n", &opts).unwrap();
+/// assert_eq!(sites["site"].hosts, vec!["lichost1", "lichost2"]);
+/// ```
+pub fn from_str_with<T>(input: &str, opts: &ParserOptions) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    let config = Config::parse_with(input, opts)?;
+    let defaults = config.defaults();
+    let sections = config
+        .sections()
+        .filter(|section| !defaults.is_some_and(|defaults| std::ptr::eq(defaults, *section)))
+        .filter_map(|section| config.get(section.name()))
+        .collect();
+    T::deserialize(ConfigDeserializer {
+        sections,
+        separator: &opts.list_separator,
+    })
+}
+
+/// Deserialize an instance of T from the cfg at the supplied path, parsed
+/// with the default ParserOptions. See crate::from_path, which reads the
+/// cfg into a ConfigOwned instead.
+///
+/// ```
+/// use serde::Deserialize;
+/// use std::collections::HashMap;
+/// # use std::path::PathBuf;
+/// # let mut cfgpath = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+/// # cfgpath.push("example_data");
+/// # cfgpath.push("operating_systems.cfg");
+///
+/// #[derive(Deserialize)]
+/// struct OperatingSystem {
+///     architecture: String,
+///     bits: u8,
+/// }
+///
+/// let systems: HashMap<String, OperatingSystem> = cfgparser::from_file(cfgpath).unwrap();
+/// assert_eq!(systems["cent7_64"].architecture, "linux_cent7_x86_64");
+/// assert_eq!(systems["cent7_64"].bits, 64);
+/// ```
+pub fn from_file<T, P>(cfg_path: P) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    from_file_with(cfg_path, &ParserOptions::default())
+}
+
+/// Deserialize an instance of T from the cfg at the supplied path, parsed
+/// with the supplied ParserOptions, whose list separator splits sequences.
+/// See crate::from_path_with.
+pub fn from_file_with<T, P>(cfg_path: P, opts: &ParserOptions) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let config = crate::from_path_with(cfg_path, opts)?;
+    deserialize_config(&config, &opts.list_separator)
+}
+
+/// Deserialize an instance of T from a config which has already been
+/// parsed, splitting sequences on the default list separator
+pub fn from_config<T>(config: &ConfigOwned) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    deserialize_config(config, &ParserOptions::default().list_separator)
+}
+
+fn deserialize_config<T>(config: &ConfigOwned, separator: &str) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    let defaults = config.defaults();
+    let sections = config
+        .iter()
+        .filter(|(_, section)| !defaults.is_some_and(|defaults| std::ptr::eq(defaults, *section)))
+        .filter_map(|(name, _)| config.get(name))
+        .collect();
+    T::deserialize(ConfigDeserializer {
+        sections,
+        separator,
+    })
+}
+
+impl<'de> de::Deserialize<'de> for SectionOwned {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(SectionVisitor)
+    }
+}
+
+struct SectionVisitor;
+
+impl<'de> Visitor<'de> for SectionVisitor {
+    type Value = SectionOwned;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map of keys to values")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut section = SectionOwned::new();
+        while let Some((key, value)) = map.next_entry::<String, String>()? {
+            section.insert(key, value);
+        }
+        Ok(section)
+    }
+}
+
+// record where an error was found, unless it already says
+pub(crate) fn locate(error: Error, at_section: &str, at_key: Option<&str>) -> Error {
+    match error {
+        Error::Serde {
+            section: None,
+            key: None,
+            message,
+        } => Error::Serde {
+            section: Some(at_section.to_string()),
+            key: at_key.map(str::to_string),
+            message,
+        },
+        error => error,
+    }
+}
+
+// presents a config as a map of section names to sections
+struct ConfigDeserializer<'c> {
+    sections: Vec<SectionView<'c>>,
+    // splits values deserialized as sequences
+    separator: &'c str,
+}
+
+impl<'de> de::Deserializer<'de> for ConfigDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(ConfigAccess {
+            sections: self.sections.into_iter(),
+            separator: self.separator,
+            current: None,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct ConfigAccess<'c> {
+    sections: std::vec::IntoIter<SectionView<'c>>,
+    separator: &'c str,
+    // the section whose name was handed out last
+    current: Option<SectionView<'c>>,
+}
+
+impl<'de> MapAccess<'de> for ConfigAccess<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let view = match self.sections.next() {
+            Some(view) => view,
+            None => return Ok(None),
+        };
+        let name: StrDeserializer<'_, Error> = view.name().into_deserializer();
+        self.current = Some(view);
+        seed.deserialize(name).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let view = self
+            .current
+            .take()
+            .ok_or_else(|| de::Error::custom("section requested before its name"))?;
+        let name = view.name();
+        seed.deserialize(SectionDeserializer {
+            view,
+            separator: self.separator,
+        })
+        .map_err(|e| locate(e, name, None))
+    }
+}
+
+// presents a section as a map of keys to values
+struct SectionDeserializer<'c> {
+    view: SectionView<'c>,
+    separator: &'c str,
+}
+
+impl<'de> de::Deserializer<'de> for SectionDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(SectionAccess {
+            section: self.view.name(),
+            items: self.view.iter().collect::<Vec<_>>().into_iter(),
+            separator: self.separator,
+            current: None,
+        })
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct enum
+        identifier ignored_any
+    }
+}
+
+struct SectionAccess<'c> {
+    section: &'c str,
+    items: std::vec::IntoIter<ViewItem<'c>>,
+    separator: &'c str,
+    // the item whose key was handed out last
+    current: Option<ViewItem<'c>>,
+}
+
+impl<'de> MapAccess<'de> for SectionAccess<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let item = match self.items.next() {
+            Some(item) => item,
+            None => return Ok(None),
+        };
+        self.current = Some(item);
+        let key: StrDeserializer<'_, Error> = item.key.into_deserializer();
+        seed.deserialize(key).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let item = self
+            .current
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before its key"))?;
+        let value = ValueDeserializer {
+            section: self.section,
+            key: item.key,
+            value: item.value,
+            separator: self.separator,
+        };
+        seed.deserialize(value)
+            .map_err(|e| locate(e, self.section, Some(item.key)))
+    }
+}
+
+// presents a single value, coercing it into the type requested
+#[derive(Clone, Copy)]
+struct ValueDeserializer<'c> {
+    section: &'c str,
+    key: &'c str,
+    value: &'c str,
+    // splits the value when it is deserialized as a sequence
+    separator: &'c str,
+}
+
+impl ValueDeserializer<'_> {
+    fn parse<T>(&self) -> Result<T, Error>
+    where
+        T: FromStr,
+    {
+        convert(Some(self.section), self.key, self.value)
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for ValueDeserializer<'_> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_str(self.value)
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_bool(to_bool(Some(self.section), self.key, self.value)?)
+    }
+
+    fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_i8(self.parse()?)
+    }
+
+    fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_i16(self.parse()?)
+    }
+
+    fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_i32(self.parse()?)
+    }
+
+    fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_i64(self.parse()?)
+    }
+
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_i128(self.parse()?)
+    }
+
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u8(self.parse()?)
+    }
+
+    fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u16(self.parse()?)
+    }
+
+    fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u32(self.parse()?)
+    }
+
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u64(self.parse()?)
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u128(self.parse()?)
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_f32(self.parse()?)
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_f64(self.parse()?)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_char(self.parse()?)
+    }
+
+    // an empty value is treated as missing
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.value.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let items = split_list(self.value, self.separator)
+            .into_iter()
+            .map(|value| ValueDeserializer { value, ..self });
+        let mut seq = SeqDeserializer::new(items);
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let variant: StrDeserializer<'_, Error> = self.value.into_deserializer();
+        visitor.visit_enum(variant)
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/de.rs"]
+mod unit_tests;
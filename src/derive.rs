@@ -0,0 +1,86 @@
+//! derive
+//!
+//! Support for the code generated by `#[derive(CfgSection)]`, available
+//! with the `derive` feature. This module is not part of the public API, and
+//! may change without notice.
+use crate::error::Error;
+use crate::typed::FromValue;
+use crate::SectionOwned;
+use std::borrow::Cow;
+use std::env;
+
+pub use crate::checks::{at_least, at_most, one_of};
+
+/// A constraint placed upon a field, given the text of its value and the
+/// converted value, which returns a description of the constraint when it
+/// is broken
+pub type Check<'c, T> = &'c dyn Fn(&str, &T) -> Result<(), String>;
+
+/// Retrieve a field which must be present. Returns None, having recorded
+/// why, when the field is missing or invalid.
+pub fn required<T>(
+    section: &SectionOwned,
+    key: &str,
+    var: Option<&str>,
+    default: Option<&str>,
+    checks: &[Check<'_, T>],
+    errors: &mut Vec<Error>,
+) -> Option<T>
+where
+    T: FromValue,
+{
+    match optional(section, key, var, default, checks, errors)? {
+        Some(value) => Some(value),
+        None => {
+            errors.push(Error::MissingKey {
+                section: None,
+                key: key.to_string(),
+            });
+            None
+        }
+    }
+}
+
+/// Retrieve a field which may be missing. Returns None, having recorded
+/// why, when the field is invalid. The value is read from the environment
+/// variable, if one is named and set, then the key, then the default.
+pub fn optional<T>(
+    section: &SectionOwned,
+    key: &str,
+    var: Option<&str>,
+    default: Option<&str>,
+    checks: &[Check<'_, T>],
+    errors: &mut Vec<Error>,
+) -> Option<Option<T>>
+where
+    T: FromValue,
+{
+    let text = var
+        .and_then(|var| env::var(var).ok())
+        .map(Cow::Owned)
+        .or_else(|| section.get(key).map(Cow::Borrowed))
+        .or_else(|| default.map(Cow::Borrowed));
+    let text = match text {
+        Some(text) => text,
+        None => return Some(None),
+    };
+    let value = match T::from_value(None, key, &text) {
+        Ok(value) => value,
+        Err(error) => {
+            errors.push(error);
+            return None;
+        }
+    };
+    let count = errors.len();
+    for check in checks {
+        if let Err(message) = check(&text, &value) {
+            errors.push(Error::Constraint {
+                section: None,
+                key: key.to_string(),
+                value: text.to_string(),
+                message,
+            });
+        }
+    }
+    Some(Some(value)).filter(|_| errors.len() == count)
+}
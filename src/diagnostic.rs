@@ -0,0 +1,223 @@
+//! diagnostic
+//!
+//! Renders an Error in the style of rustc, so that the person who
+//! broke the cfg can see which line is at fault and why:
+//!
+//! ```notrust
+//! error: illegal character ' ' in value
+//!  --> operating_systems.cfg:3:10
+//!   |
+//! 3 | key = two words
+//!   |          ^
+//!   = hint: values may not contain spaces; quote the value
+//! ```
+use crate::error::{Error, Location};
+use std::fmt;
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Controls whether rendered diagnostics include ANSI color codes
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Style {
+    /// Plain text, suitable for logs and files
+    Plain,
+    /// Text highlighted with ANSI color codes, suitable for a terminal
+    Colored,
+}
+
+/// A renderable description of a problem found in a cfg.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Diagnostic {
+    /// What went wrong
+    pub message: String,
+    /// Where it went wrong, if known
+    pub location: Option<Location>,
+    /// A suggestion as to how to fix the problem
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    /// Render the diagnostic as a multi line string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, Diagnostic, Style};
+    ///
+    /// let error = Config::parse_cfg_from_str("[test]\nkey = two words\n").unwrap_err();
+    /// let rendered = Diagnostic::from(&error).render(Style::Plain);
+    /// assert_eq!(
+    ///     rendered,
+    ///     "error: illegal character ' ' in value\n --> <input>:2:10\n  |\n2 | key = two words\n  |          ^\n  = hint: values may not contain spaces; quote the value\n"
+    /// );
+    /// ```
+    pub fn render(&self, style: Style) -> String {
+        let (red, blue, bold, reset) = match style {
+            Style::Colored => (RED, BLUE, BOLD, RESET),
+            Style::Plain => ("", "", "", ""),
+        };
+        let mut out = format!("{}error{}{}: {}{}\n", red, reset, bold, self.message, reset);
+        let gutter = match &self.location {
+            Some(location) => {
+                let gutter = " ".repeat(location.line.to_string().len());
+                let path = location
+                    .path
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|| "<input>".to_string());
+                out.push_str(&format!(
+                    "{}{}-->{} {}:{}:{}\n",
+                    gutter, blue, reset, path, location.line, location.column
+                ));
+                out.push_str(&format!("{} {}|{}\n", gutter, blue, reset));
+                out.push_str(&format!(
+                    "{}{} |{} {}\n",
+                    blue, location.line, reset, location.source_line
+                ));
+                out.push_str(&format!(
+                    "{} {}|{} {}{}{}{}\n",
+                    gutter,
+                    blue,
+                    reset,
+                    caret_padding(location),
+                    red,
+                    "^".repeat(location.length),
+                    reset
+                ));
+                gutter
+            }
+            None => String::new(),
+        };
+        if let Some(hint) = &self.hint {
+            out.push_str(&format!(
+                "{} {}={} {}hint{}: {}\n",
+                gutter, blue, reset, bold, reset, hint
+            ));
+        }
+        out
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(Style::Plain))
+    }
+}
+
+impl From<&Error> for Diagnostic {
+    fn from(error: &Error) -> Self {
+        Self {
+            message: error.message(),
+            location: error.location().cloned(),
+            hint: hint(error),
+        }
+    }
+}
+
+// whitespace lining the caret up with the column, preserving any tabs in
+// the source line so that the caret lands in the right spot
+fn caret_padding(location: &Location) -> String {
+    location
+        .source_line
+        .chars()
+        .take(location.column - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect()
+}
+
+// suggest a fix for the supplied error
+fn hint(error: &Error) -> Option<String> {
+    let hint = match error {
+        Error::InvalidHeader(location) if !location.source_line.contains(']') => {
+            "close the section header with ']'".to_string()
+        }
+        Error::InvalidHeader(_) => {
+            "section names start with a letter, followed by letters, numbers and single underscores"
+                .to_string()
+        }
+        Error::InvalidKey(_) => {
+            "keys start with a letter, followed by letters, numbers and single underscores"
+                .to_string()
+        }
+        Error::MissingDelimiter(_) => {
+            "separate the key from its value with a delimiter, such as '='".to_string()
+        }
+        Error::IllegalCharacter { character, .. } if character.is_whitespace() => {
+            "values may not contain spaces; quote the value".to_string()
+        }
+        Error::IllegalCharacter { character, .. } => {
+            format!("values may not contain {:?}; quote the value", character)
+        }
+        Error::UnterminatedQuote(_) => {
+            "close the value with the same quote it was opened with".to_string()
+        }
+        Error::InvalidEscape(_) => {
+            r#"supported escapes are \", \', \\, \n, \t, \r, \0 and \u{...}"#.to_string()
+        }
+        Error::DuplicateSection { name, .. } => {
+            format!("move these keys into the first [{}] section", name)
+        }
+        Error::DuplicateKey { key, .. } => {
+            format!("remove all but one of the definitions of '{}'", key)
+        }
+        Error::MissingHeader(_) => "add a [section] header above this line".to_string(),
+        Error::EmptySection { .. } => {
+            "add at least one key to the section, or remove the header".to_string()
+        }
+        Error::InvalidInterpolation { .. } => {
+            "references are written as ${key}, ${section:key} or %(key)s; escape a literal '$' or '%' by doubling it"
+                .to_string()
+        }
+        Error::MissingReference { chain } => format!(
+            "define '{}', or escape the reference",
+            chain.last().map(String::as_str).unwrap_or_default()
+        ),
+        Error::InterpolationCycle { .. } => {
+            "break the cycle by replacing one of the references with a value".to_string()
+        }
+        Error::InterpolationDepth { .. } => {
+            "reduce the nesting of references, or raise Interpolator::max_depth".to_string()
+        }
+        Error::UndefinedVariable { variable, .. } => format!(
+            "set {}, or supply a default using ${{{}:-default}}",
+            variable, variable
+        ),
+        Error::InvalidVariable { .. } => {
+            "variables are written as $VAR, ${VAR} or ${VAR:-default}; escape a literal '$' by doubling it"
+                .to_string()
+        }
+        Error::InvalidValue { target, .. } if target == "bool" => {
+            "use one of yes/no, on/off, true/false or 1/0".to_string()
+        }
+        Error::InvalidValue { key, target, .. } => {
+            format!("set '{}' to a value which parses as {}", key, target)
+        }
+        Error::MissingKey { key, .. } => format!("add a value for '{}' to the section", key),
+        Error::Constraint { key, .. } => {
+            format!("change the value of '{}' to one which is permitted", key)
+        }
+        Error::MissingParent { parent, .. } => {
+            format!("define a [{}] section, or inherit from another", parent)
+        }
+        Error::InheritanceCycle { .. } => {
+            "remove the parent from one of the sections in the cycle".to_string()
+        }
+        Error::InvalidInclude(_) => {
+            "name the file to include, such as '%include base.cfg' or '%include conf.d/*.cfg'"
+                .to_string()
+        }
+        Error::IncludeCycle { .. } => "remove one of the include directives".to_string(),
+        Error::InvalidName(_) => {
+            "names start with a letter, followed by letters, numbers and underscores".to_string()
+        }
+        Error::InvalidSchema { .. } | Error::Serde { .. } | Error::Io { .. } => return None,
+    };
+    Some(hint)
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/diagnostic.rs"]
+mod unit_tests;
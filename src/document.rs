@@ -0,0 +1,548 @@
+//! document
+//!
+//! This module houses Document, which holds a cfg as the lines of text it
+//! was read from, rather than as a map of sections. Comments, blank lines,
+//! indentation, quoting and line endings are all kept, so that a hand
+//! maintained cfg may be edited by a tool and written back out with only the
+//! edited lines changed.
+use crate::error::{Error, Location};
+use crate::parser::driver::{fold_key, key_value_line, parse_header, parse_lines, INHERITS};
+use crate::view::Items;
+use crate::writer::{is_valid_name, write_value};
+use crate::{Config, Continuation, DuplicatePolicy, Names, ParserOptions, Quoting};
+use std::fmt;
+use std::ops::Range;
+
+/// A cfg which may be edited without disturbing its formatting.
+///
+/// Writing a Document, via Display, reproduces the text it was parsed from
+/// byte for byte, apart from the lines touched by edits. New keys are
+/// written after the last key of their section, using the indentation of
+/// their neighbours and the delimiter already used within the cfg.
+///
+/// # Example
+///
+/// ```
+/// use cfgparser::{Document, ParserOptions};
+///
+/// let contents = "# operating systems\n[cent7_64]\nbits = 64  # word size\n\n[cent6_64]\nbits = 64\n";
+/// let opts = ParserOptions::new().inline_comment_prefixes(vec!["#"]);
+/// let mut doc = Document::parse_with(contents, &opts).unwrap();
+/// assert_eq!(doc.to_string(), contents);
+///
+/// doc.set("cent7_64", "bits", "32").unwrap();
+/// doc.insert_after("cent7_64", "bits", "arch", "x86").unwrap();
+/// doc.rename_section("cent6_64", "cent6_32").unwrap();
+/// assert_eq!(
+///     doc.to_string(),
+///     "# operating systems\n[cent7_64]\nbits = 32  # word size\narch = x86\n\n[cent6_32]\nbits = 64\n"
+/// );
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Document {
+    lines: Vec<Line>,
+    opts: ParserOptions,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Line {
+    text: String,
+    // "\n", "\r\n", or empty for a final line without one
+    ending: &'static str,
+}
+
+// what a line holds, as far as editing is concerned. Byte ranges are
+// relative to the text of the line.
+enum Kind {
+    Header {
+        name: Range<usize>,
+        // the parent named by an inheriting header, such as `[child : parent]`
+        parent: Option<Range<usize>>,
+        replaces: bool,
+    },
+    // the value range includes any quotes
+    Entry {
+        key: Range<usize>,
+        value: Range<usize>,
+    },
+    // continues the value of the entry above it
+    Continuation,
+    // blank lines, comments, and lines such as tombstones which are not edited
+    Other,
+}
+
+impl Document {
+    /// Parse a document from a string, using the default ParserOptions
+    pub fn parse_cfg_from_str(input: &str) -> Result<Self, Error> {
+        Self::parse_with(input, &ParserOptions::default())
+    }
+
+    /// Parse a document from a string, using the supplied ParserOptions to
+    /// control the grammar. The options are kept, and govern later edits.
+    pub fn parse_with(input: &str, opts: &ParserOptions) -> Result<Self, Error> {
+        Config::parse_with(input, opts)?;
+        let lines = input
+            .split_inclusive('\n')
+            .map(|raw| {
+                let (text, ending) = match raw.strip_suffix('\n') {
+                    Some(text) => match text.strip_suffix('\r') {
+                        Some(text) => (text, "\r\n"),
+                        None => (text, "\n"),
+                    },
+                    None => (raw, ""),
+                };
+                Line {
+                    text: text.to_string(),
+                    ending,
+                }
+            })
+            .collect();
+        Ok(Self {
+            lines,
+            opts: opts.clone(),
+        })
+    }
+
+    /// Retrieve the value of a key within a section, as the section itself
+    /// defines it. Keys supplied by a defaults section, or by a parent
+    /// section, are not consulted.
+    pub fn get(&self, section: &str, key: &str) -> Option<String> {
+        let kinds = self.scan();
+        let lines = self.locate(&kinds, section, key)?;
+        // parse the entry on its own, so that quotes, escapes and
+        // continuations are handled exactly as they are by Config
+        let mut text = "[document]\n".to_string();
+        for line in self.lines[lines].iter() {
+            text.push_str(&line.text);
+            text.push('\n');
+        }
+        let opts = self.opts.clone().inheritance(false);
+        let sections = parse_lines(&text, &opts).ok()?;
+        let key = fold_key(key, self.opts.fold_case);
+        let (_, value) = sections.first()?.item(&key)?;
+        Some(value.to_string())
+    }
+
+    /// Set the value of a key within a section. An existing value is replaced
+    /// in place, keeping any inline comment which follows it. A new key is
+    /// added after the last key of the section, and a new section is added
+    /// at the end of the document.
+    pub fn set(&mut self, section: &str, key: &str, value: &str) -> Result<(), Error> {
+        check_name(section, self.opts.names)?;
+        check_name(key, self.opts.names)?;
+        let kinds = self.scan();
+        if let Some(lines) = self.locate(&kinds, section, key) {
+            self.replace_value(&kinds, lines, value);
+            return Ok(());
+        }
+        match self.bodies(&kinds, section).last() {
+            Some(body) => {
+                let last = body
+                    .clone()
+                    .rev()
+                    .find(|idx| matches!(kinds[*idx], Kind::Entry { .. }));
+                let at = match last {
+                    Some(idx) => self.entry_end(&kinds, idx),
+                    None => body.start,
+                };
+                let line = self.entry_line(&kinds, last, key, value);
+                self.insert_line(at, line);
+            }
+            None => {
+                let line = self.entry_line(&kinds, None, key, value);
+                if self.lines.last().is_some_and(|l| !l.text.trim().is_empty()) {
+                    self.insert_line(self.lines.len(), String::new());
+                }
+                self.insert_line(self.lines.len(), format!("[{}]", section));
+                self.insert_line(self.lines.len(), line);
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a key to a section, directly after an existing key. If the section
+    /// already holds the new key, it is moved. Returns false, leaving the
+    /// document untouched, if the section does not hold the existing key.
+    pub fn insert_after(
+        &mut self,
+        section: &str,
+        after: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<bool, Error> {
+        check_name(section, self.opts.names)?;
+        check_name(key, self.opts.names)?;
+        let fold = self.opts.fold_case;
+        if self.locate(&self.scan(), section, after).is_none() {
+            return Ok(false);
+        }
+        if fold_key(after, fold) == fold_key(key, fold) {
+            self.set(section, key, value)?;
+            return Ok(true);
+        }
+        self.remove(section, key);
+        let kinds = self.scan();
+        let anchor = match self.locate(&kinds, section, after) {
+            Some(anchor) => anchor,
+            None => return Ok(false),
+        };
+        let line = self.entry_line(&kinds, Some(anchor.start), key, value);
+        self.insert_line(anchor.end, line);
+        Ok(true)
+    }
+
+    /// Remove a key from a section, along with any lines continuing its value,
+    /// returning the value it held
+    pub fn remove(&mut self, section: &str, key: &str) -> Option<String> {
+        let value = self.get(section, key)?;
+        let lines = self.locate(&self.scan(), section, key)?;
+        self.remove_lines(lines);
+        Some(value)
+    }
+
+    /// Rename every header of a section, leaving its keys and comments alone.
+    /// When inheritance is enabled, sections naming it as their parent, via
+    /// their header or an `inherits` key, are updated to the new name too.
+    /// Returns false if no such section has a header. Fails if the new name is
+    /// invalid, or another section already has it.
+    pub fn rename_section(&mut self, from: &str, to: &str) -> Result<bool, Error> {
+        check_name(to, self.opts.names)?;
+        let kinds = self.scan();
+        let mut found = false;
+        // the spans to replace, along with their replacement
+        let mut edits = Vec::new();
+        for (idx, kind) in kinds.iter().enumerate() {
+            let text = &self.lines[idx].text;
+            match kind {
+                Kind::Header { name, parent, .. } => {
+                    let current = &text[name.clone()];
+                    if current == to && from != to {
+                        return Err(Error::DuplicateSection {
+                            name: to.to_string(),
+                            location: Location::new(idx + 1, text, indent(text).len())
+                                .with_length(text.trim().chars().count()),
+                        });
+                    }
+                    if current == from {
+                        found = true;
+                        edits.push((idx, name.clone(), to.to_string()));
+                    }
+                    if let Some(parent) = parent
+                        .clone()
+                        .filter(|parent| &text[parent.clone()] == from)
+                    {
+                        edits.push((idx, parent, to.to_string()));
+                    }
+                }
+                Kind::Entry { value, .. } if self.inherits_from(idx, from) => {
+                    edits.push((idx, value.clone(), format_value(to)));
+                }
+                _ => (),
+            }
+        }
+        // replace from the end of each line, so earlier spans stay valid
+        for (idx, range, replacement) in edits.into_iter().rev() {
+            self.lines[idx].text.replace_range(range, &replacement);
+        }
+        Ok(found)
+    }
+
+    // classify each line, following the same rules as the parser
+    fn scan(&self) -> Vec<Kind> {
+        let mut kinds = Vec::with_capacity(self.lines.len());
+        // indentation of the most recent key, and whether the line before
+        // ended with a backslash continuing its value
+        let mut pending: Option<(usize, bool)> = None;
+        for (idx, line) in self.lines.iter().enumerate() {
+            let text = line.text.as_str();
+            let trimmed = text.trim();
+            if let Some((key_indent, escaped)) = pending {
+                if self.continues(text, key_indent, escaped) {
+                    let mut rest = trimmed;
+                    if let Some(end) = self.opts.find_inline_comment(rest) {
+                        rest = rest[..end].trim_end();
+                    }
+                    pending = Some((key_indent, rest.ends_with('\\')));
+                    kinds.push(Kind::Continuation);
+                    continue;
+                }
+            }
+            // a blank line leaves a pending value pending, as it may be
+            // followed by further continuation lines
+            if trimmed.is_empty() {
+                kinds.push(Kind::Other);
+                continue;
+            }
+            if self.opts.is_comment(trimmed) {
+                kinds.push(Kind::Other);
+                continue;
+            }
+            pending = None;
+            let kind = if trimmed.starts_with('[') {
+                match parse_header(text, &self.opts) {
+                    Ok((_, (name, parent, replaces))) => Kind::Header {
+                        name: span(text, name),
+                        parent: parent.map(|parent| span(text, parent)),
+                        replaces,
+                    },
+                    Err(_) => Kind::Other,
+                }
+            } else if self.opts.tombstones && trimmed.starts_with('!') {
+                Kind::Other
+            } else {
+                match key_value_line(idx + 1, text, &self.opts) {
+                    Ok((key, raw, _)) => {
+                        let escaped = text.trim_end().ends_with('\\') && raw.ends_with('\\');
+                        pending = Some((indent(text).len(), escaped));
+                        Kind::Entry {
+                            key: span(text, key),
+                            value: span(text, raw),
+                        }
+                    }
+                    Err(_) => Kind::Other,
+                }
+            };
+            kinds.push(kind);
+        }
+        kinds
+    }
+
+    // is the line an `inherits` key naming the section as its parent
+    fn inherits_from(&self, idx: usize, section: &str) -> bool {
+        if !self.opts.inheritance {
+            return false;
+        }
+        match key_value_line(idx + 1, &self.lines[idx].text, &self.opts) {
+            Ok((key, _, value)) => {
+                fold_key(key, self.opts.fold_case) == INHERITS && value == section
+            }
+            Err(_) => false,
+        }
+    }
+
+    // does the line continue the value of the key above it
+    fn continues(&self, text: &str, key_indent: usize, escaped: bool) -> bool {
+        match self.opts.continuation {
+            Continuation::Backslash => escaped,
+            Continuation::Indented => {
+                let trimmed = text.trim();
+                !trimmed.is_empty()
+                    && !self.opts.is_comment(trimmed)
+                    && indent(text).len() > key_indent
+            }
+            Continuation::Disabled => false,
+        }
+    }
+
+    // The lines following each header of the section, up to the next header,
+    // which contribute keys to it. Lines before the first header form the
+    // global section, if there is one.
+    fn bodies(&self, kinds: &[Kind], section: &str) -> Vec<Range<usize>> {
+        let next_header = |from: usize| {
+            kinds[from..]
+                .iter()
+                .position(|kind| matches!(kind, Kind::Header { .. }))
+                .map_or(kinds.len(), |found| from + found)
+        };
+        let mut bodies = Vec::new();
+        for (idx, kind) in kinds.iter().enumerate() {
+            if let Kind::Header { name, replaces, .. } = kind {
+                if &self.lines[idx].text[name.clone()] != section {
+                    continue;
+                }
+                if *replaces {
+                    bodies.clear();
+                }
+                bodies.push(idx + 1..next_header(idx + 1));
+            }
+        }
+        if bodies.is_empty() && self.opts.global_section.as_deref() == Some(section) {
+            bodies.push(0..next_header(0));
+        }
+        match self.opts.duplicate_sections {
+            DuplicatePolicy::KeepFirst => bodies.truncate(1),
+            DuplicatePolicy::KeepLast => {
+                let last = bodies.len().saturating_sub(1);
+                bodies.drain(..last);
+            }
+            DuplicatePolicy::Error | DuplicatePolicy::Merge => (),
+        }
+        bodies
+    }
+
+    // the lines holding the key, and the continuation of its value, within the
+    // section. When a key is repeated, the occurrence the parser keeps is used.
+    fn locate(&self, kinds: &[Kind], section: &str, key: &str) -> Option<Range<usize>> {
+        let fold = self.opts.fold_case;
+        let key = fold_key(key, fold);
+        let keep_first = self.opts.duplicate_keys == DuplicatePolicy::KeepFirst;
+        let mut found = None;
+        for body in self.bodies(kinds, section) {
+            for idx in body {
+                if let Kind::Entry { key: range, .. } = &kinds[idx] {
+                    let matches = fold_key(&self.lines[idx].text[range.clone()], fold) == key;
+                    if matches && !(keep_first && found.is_some()) {
+                        found = Some(idx..self.entry_end(kinds, idx));
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    // index of the line following the entry and its continuation lines. As
+    // in the parser, comments and blank lines between the continuation lines
+    // do not end the value, so they are taken as part of the entry.
+    fn entry_end(&self, kinds: &[Kind], idx: usize) -> usize {
+        let mut end = idx + 1;
+        for (next, kind) in kinds.iter().enumerate().skip(idx + 1) {
+            match kind {
+                Kind::Continuation => end = next + 1,
+                Kind::Other if self.is_comment_line(next) || self.is_blank_line(next) => (),
+                _ => break,
+            }
+        }
+        end
+    }
+
+    // is the line a comment, which leaves a pending value pending
+    fn is_comment_line(&self, idx: usize) -> bool {
+        let trimmed = self.lines[idx].text.trim();
+        !trimmed.is_empty() && self.opts.is_comment(trimmed)
+    }
+
+    fn is_blank_line(&self, idx: usize) -> bool {
+        self.lines[idx].text.trim().is_empty()
+    }
+
+    // replace the value of the entry spanning the supplied lines
+    fn replace_value(&mut self, kinds: &[Kind], lines: Range<usize>, value: &str) {
+        if let Kind::Entry { key, value: span } = &kinds[lines.start] {
+            let text = &self.lines[lines.start].text;
+            // a key written without a value gains a delimiter
+            let mut replacement = if self.opts.contains_delimiter(&text[key.end..span.start]) {
+                String::new()
+            } else {
+                self.delimiter(kinds, None)
+            };
+            replacement.push_str(&format_value(value));
+            self.lines[lines.start]
+                .text
+                .replace_range(span.clone(), &replacement);
+        }
+        self.remove_lines(lines.start + 1..lines.end);
+    }
+
+    // the text of a new entry, formatted like the neighbouring entry if there is one
+    fn entry_line(
+        &self,
+        kinds: &[Kind],
+        neighbour: Option<usize>,
+        key: &str,
+        value: &str,
+    ) -> String {
+        let indent = neighbour.map_or("", |idx| indent(&self.lines[idx].text));
+        format!(
+            "{}{}{}{}",
+            indent,
+            key,
+            self.delimiter(kinds, neighbour),
+            format_value(value)
+        )
+    }
+
+    // the delimiter, along with the spaces around it, used by the neighbouring
+    // entry, or else by the first entry holding one, falling back to the first
+    // of the configured delimiters
+    fn delimiter(&self, kinds: &[Kind], neighbour: Option<usize>) -> String {
+        let between = |idx: usize| match &kinds[idx] {
+            Kind::Entry { key, value } => Some(&self.lines[idx].text[key.end..value.start])
+                .filter(|between| self.opts.contains_delimiter(between)),
+            _ => None,
+        };
+        let used = neighbour
+            .and_then(between)
+            .or_else(|| (0..kinds.len()).find_map(between));
+        match used {
+            Some(used) => used.to_string(),
+            None => format!(
+                " {} ",
+                self.opts.delimiters.first().map_or("=", |d| d.as_str())
+            ),
+        }
+    }
+
+    // the line ending used by the document
+    fn ending(&self) -> &'static str {
+        self.lines
+            .iter()
+            .map(|line| line.ending)
+            .find(|ending| !ending.is_empty())
+            .unwrap_or("\n")
+    }
+
+    // insert a line, keeping a missing newline at the end of the document missing
+    fn insert_line(&mut self, at: usize, text: String) {
+        let mut ending = self.ending();
+        if at == self.lines.len() {
+            if let Some(last) = self.lines.last_mut().filter(|last| last.ending.is_empty()) {
+                last.ending = ending;
+                ending = "";
+            }
+        }
+        self.lines.insert(at, Line { text, ending });
+    }
+
+    // remove lines, keeping a missing newline at the end of the document missing
+    fn remove_lines(&mut self, lines: Range<usize>) {
+        if lines.is_empty() {
+            return;
+        }
+        if lines.end == self.lines.len() && self.lines[lines.end - 1].ending.is_empty() {
+            if let Some(previous) = lines.start.checked_sub(1) {
+                self.lines[previous].ending = "";
+            }
+        }
+        self.lines.drain(lines);
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.lines.iter() {
+            write!(f, "{}{}", line.text, line.ending)?;
+        }
+        Ok(())
+    }
+}
+
+fn check_name(name: &str, names: Names) -> Result<(), Error> {
+    if is_valid_name(name, names) {
+        Ok(())
+    } else {
+        Err(Error::InvalidName(name.to_string()))
+    }
+}
+
+// the value as it should be written, quoted if necessary
+fn format_value(value: &str) -> String {
+    let mut formatted = String::new();
+    // writing to a String can not fail
+    let _ = write_value(&mut formatted, value, Quoting::WhenNeeded);
+    formatted
+}
+
+// the byte range of a slice of the line within it
+fn span(line: &str, part: &str) -> Range<usize> {
+    let start = part.as_ptr() as usize - line.as_ptr() as usize;
+    start..start + part.len()
+}
+
+// the whitespace at the start of the line
+fn indent(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/document.rs"]
+mod unit_tests;
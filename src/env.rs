@@ -0,0 +1,120 @@
+//! env
+//!
+//! This module houses VariableSource, which supplies the variables
+//! referenced by `$VAR`, `${VAR}` and `${VAR:-default}` within a value, along
+//! with Environment, which reads them from the environment of the process.
+//! Expansion is opt-in, via Config::get_expanded.
+use crate::error::Error;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+/// A source of variables for expansion within values. Implemented for
+/// Environment, and for maps of names to values, which is handy in tests.
+pub trait VariableSource {
+    /// Retrieve the value of the named variable, if it is defined
+    fn var(&self, name: &str) -> Option<String>;
+}
+
+/// Reads variables from the environment of the process
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Environment;
+
+impl VariableSource for Environment {
+    fn var(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+}
+
+impl<S: BuildHasher> VariableSource for HashMap<String, String, S> {
+    fn var(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+impl<S: BuildHasher> VariableSource for HashMap<&str, &str, S> {
+    fn var(&self, name: &str) -> Option<String> {
+        self.get(name).map(|value| value.to_string())
+    }
+}
+
+// Expand the variables within the value of the key in the section. A '$'
+// which does not start a variable is left alone, and `$$` yields a single '$'.
+pub(crate) fn expand(
+    source: &dyn VariableSource,
+    section: &str,
+    key: &str,
+    value: &str,
+) -> Result<String, Error> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(idx) = rest.find('$') {
+        expanded.push_str(&rest[..idx]);
+        rest = &rest[idx + 1..];
+        if let Some(after) = rest.strip_prefix('$') {
+            expanded.push('$');
+            rest = after;
+        } else if let Some(inner) = rest.strip_prefix('{') {
+            let end = inner.find('}').ok_or_else(|| Error::InvalidVariable {
+                section: section.to_string(),
+                key: key.to_string(),
+            })?;
+            let (name, default) = match inner[..end].find(":-") {
+                Some(sep) => (&inner[..sep], Some(&inner[sep + 2..end])),
+                None => (&inner[..end], None),
+            };
+            if name_len(name) != name.len() || name.is_empty() {
+                return Err(Error::InvalidVariable {
+                    section: section.to_string(),
+                    key: key.to_string(),
+                });
+            }
+            let value = match (source.var(name), default) {
+                (Some(value), Some(default)) if value.is_empty() => default.to_string(),
+                (Some(value), _) => value,
+                (None, Some(default)) => default.to_string(),
+                (None, None) => return Err(undefined(section, key, name)),
+            };
+            expanded.push_str(&value);
+            rest = &inner[end + 1..];
+        } else {
+            let len = name_len(rest);
+            if len == 0 {
+                expanded.push('$');
+                continue;
+            }
+            let name = &rest[..len];
+            let value = source
+                .var(name)
+                .ok_or_else(|| undefined(section, key, name))?;
+            expanded.push_str(&value);
+            rest = &rest[len..];
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+// length of the variable name at the start of the text. Names start with a
+// letter or underscore, followed by letters, numbers and underscores.
+fn name_len(text: &str) -> usize {
+    let mut chars = text.char_indices();
+    match chars.next() {
+        Some((_, c)) if c.is_ascii_alphabetic() || c == '_' => (),
+        _ => return 0,
+    }
+    chars
+        .find(|(_, c)| !(c.is_ascii_alphanumeric() || *c == '_'))
+        .map_or(text.len(), |(idx, _)| idx)
+}
+
+fn undefined(section: &str, key: &str, variable: &str) -> Error {
+    Error::UndefinedVariable {
+        section: section.to_string(),
+        key: key.to_string(),
+        variable: variable.to_string(),
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/env.rs"]
+mod unit_tests;
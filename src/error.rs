@@ -0,0 +1,399 @@
+//! error
+//!
+//! This module houses the crate's Error type, along with Location,
+//! which records where in a cfg a problem was found.
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The location of a problem within a cfg.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Location {
+    /// Path to the cfg, if it was read from disk
+    pub path: Option<PathBuf>,
+    /// Line number, starting at 1
+    pub line: usize,
+    /// Column, counted in chars and starting at 1
+    pub column: usize,
+    /// Length of the offending span, counted in chars
+    pub length: usize,
+    /// The full text of the offending line
+    pub source_line: String,
+}
+
+impl Location {
+    /// Create a new Location given a 1 based line number, the text of the line,
+    /// and the byte offset of the problem within the line.
+    pub fn new(line: usize, source_line: &str, byte_offset: usize) -> Self {
+        let byte_offset = byte_offset.min(source_line.len());
+        Self {
+            path: None,
+            line,
+            column: source_line[..byte_offset].chars().count() + 1,
+            length: 1,
+            source_line: source_line.to_string(),
+        }
+    }
+
+    /// Set the length, in chars, of the offending span
+    pub fn with_length(mut self, length: usize) -> Self {
+        self.length = length.max(1);
+        self
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}:{}:{}", path.display(), self.line, self.column),
+            None => write!(f, "line {}, column {}", self.line, self.column),
+        }
+    }
+}
+
+/// Errors which may be encountered while parsing or reading a cfg.
+#[derive(Debug)]
+pub enum Error {
+    /// A line starting with '[' is not a valid section header
+    InvalidHeader(Location),
+    /// The key of a key value pair is not valid
+    InvalidKey(Location),
+    /// A key is not followed by a delimiter, such as '='
+    MissingDelimiter(Location),
+    /// A value contains a character which is not permitted
+    IllegalCharacter { character: char, location: Location },
+    /// A quoted value is missing its closing quote
+    UnterminatedQuote(Location),
+    /// A quoted value contains an unknown or malformed escape sequence
+    InvalidEscape(Location),
+    /// A section header appears more than once
+    DuplicateSection { name: String, location: Location },
+    /// A key appears more than once within a section
+    DuplicateKey {
+        section: String,
+        key: String,
+        location: Location,
+    },
+    /// A key value pair appears before the first section header
+    MissingHeader(Location),
+    /// A section header is not followed by any key value pairs, and empty
+    /// sections have been disallowed
+    EmptySection { name: String, location: Location },
+    /// A value holds a malformed reference to another key. The chain names
+    /// each key followed to reach the value, as `section:key`.
+    InvalidInterpolation { chain: Vec<String> },
+    /// A value references a key which does not exist. The chain ends with
+    /// the missing key.
+    MissingReference { chain: Vec<String> },
+    /// A value references itself, directly or via other keys. The chain
+    /// starts and ends with the same key.
+    InterpolationCycle { chain: Vec<String> },
+    /// A value references keys nested more deeply than permitted
+    InterpolationDepth { chain: Vec<String> },
+    /// A value references an environment variable which is not defined
+    UndefinedVariable {
+        section: String,
+        key: String,
+        variable: String,
+    },
+    /// A value holds a malformed `${VAR}` reference to an environment variable
+    InvalidVariable { section: String, key: String },
+    /// A value could not be converted to the type requested of a typed
+    /// getter, such as get_int. The section is None when the value was read
+    /// from a SectionOwned, which does not know its own name.
+    InvalidValue {
+        section: Option<String>,
+        key: String,
+        value: String,
+        target: String,
+    },
+    /// A key required when converting a section into a Rust type is not
+    /// defined. The section is None when read from a SectionOwned.
+    MissingKey {
+        section: Option<String>,
+        key: String,
+    },
+    /// A value was converted, but breaks a constraint placed upon it, such as
+    /// a minimum or a list of allowed values. The message describes the
+    /// constraint, as in "must be at least 32".
+    Constraint {
+        section: Option<String>,
+        key: String,
+        value: String,
+        message: String,
+    },
+    /// A section inherits from a section which does not exist
+    MissingParent {
+        section: String,
+        parent: String,
+        location: Location,
+    },
+    /// A section inherits from itself, directly or via other sections. The
+    /// chain starts and ends with the same section.
+    InheritanceCycle {
+        chain: Vec<String>,
+        location: Location,
+    },
+    /// An include directive is missing its path, or holds a malformed glob
+    InvalidInclude(Location),
+    /// A cfg includes itself, directly or via other files. The chain lists
+    /// the files being included, ending with the file included again.
+    IncludeCycle {
+        chain: Vec<PathBuf>,
+        location: Location,
+    },
+    /// A section name or key supplied when editing a Document would not be
+    /// accepted by the grammar
+    InvalidName(String),
+    /// A Schema holds a rule which cannot be used, such as one with a
+    /// malformed pattern. The section is that of the schema cfg the rule was
+    /// read from, if any.
+    InvalidSchema {
+        section: Option<String>,
+        message: String,
+    },
+    /// A config could not be converted to or from a Rust type via serde.
+    /// The section and key are recorded when known.
+    Serde {
+        section: Option<String>,
+        key: Option<String>,
+        message: String,
+    },
+    /// The cfg could not be read
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+}
+
+impl Error {
+    /// Retrieve the location of the error, if it has one
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Error::InvalidHeader(location)
+            | Error::InvalidKey(location)
+            | Error::MissingDelimiter(location)
+            | Error::UnterminatedQuote(location)
+            | Error::InvalidEscape(location)
+            | Error::MissingHeader(location)
+            | Error::IllegalCharacter { location, .. }
+            | Error::DuplicateSection { location, .. }
+            | Error::DuplicateKey { location, .. }
+            | Error::EmptySection { location, .. }
+            | Error::MissingParent { location, .. }
+            | Error::InheritanceCycle { location, .. }
+            | Error::InvalidInclude(location)
+            | Error::IncludeCycle { location, .. } => Some(location),
+            Error::InvalidInterpolation { .. }
+            | Error::MissingReference { .. }
+            | Error::InterpolationCycle { .. }
+            | Error::InterpolationDepth { .. }
+            | Error::UndefinedVariable { .. }
+            | Error::InvalidVariable { .. }
+            | Error::InvalidValue { .. }
+            | Error::MissingKey { .. }
+            | Error::Constraint { .. }
+            | Error::InvalidSchema { .. }
+            | Error::InvalidName(_)
+            | Error::Serde { .. }
+            | Error::Io { .. } => None,
+        }
+    }
+
+    /// Attach the path of the cfg the error was found in
+    pub fn with_path<P>(mut self, cfg_path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let cfg_path = cfg_path.as_ref().to_path_buf();
+        if let Error::Io { path, .. } = &mut self {
+            *path = Some(cfg_path);
+        } else if let Some(location) = self.location_mut() {
+            location.path = Some(cfg_path);
+        }
+        self
+    }
+
+    // mutable access to the location of the error, if it has one
+    pub(crate) fn location_mut(&mut self) -> Option<&mut Location> {
+        match self {
+            Error::InvalidHeader(location)
+            | Error::InvalidKey(location)
+            | Error::MissingDelimiter(location)
+            | Error::UnterminatedQuote(location)
+            | Error::InvalidEscape(location)
+            | Error::MissingHeader(location)
+            | Error::IllegalCharacter { location, .. }
+            | Error::DuplicateSection { location, .. }
+            | Error::DuplicateKey { location, .. }
+            | Error::EmptySection { location, .. }
+            | Error::MissingParent { location, .. }
+            | Error::InheritanceCycle { location, .. }
+            | Error::InvalidInclude(location)
+            | Error::IncludeCycle { location, .. } => Some(location),
+            Error::InvalidInterpolation { .. }
+            | Error::MissingReference { .. }
+            | Error::InterpolationCycle { .. }
+            | Error::InterpolationDepth { .. }
+            | Error::UndefinedVariable { .. }
+            | Error::InvalidVariable { .. }
+            | Error::InvalidValue { .. }
+            | Error::MissingKey { .. }
+            | Error::Constraint { .. }
+            | Error::InvalidSchema { .. }
+            | Error::InvalidName(_)
+            | Error::Serde { .. }
+            | Error::Io { .. } => None,
+        }
+    }
+
+    /// The message describing the error, minus its location
+    pub fn message(&self) -> String {
+        match self {
+            Error::InvalidHeader(_) => "invalid section header".to_string(),
+            Error::InvalidKey(_) => "invalid key".to_string(),
+            Error::MissingDelimiter(_) => "missing delimiter between key and value".to_string(),
+            Error::IllegalCharacter { character, .. } => {
+                format!("illegal character {:?} in value", character)
+            }
+            Error::UnterminatedQuote(_) => "quoted value is missing its closing quote".to_string(),
+            Error::InvalidEscape(_) => "invalid escape sequence in quoted value".to_string(),
+            Error::DuplicateSection { name, .. } => format!("duplicate section '{}'", name),
+            Error::DuplicateKey { section, key, .. } => {
+                format!("duplicate key '{}' in section '{}'", key, section)
+            }
+            Error::MissingHeader(_) => "key value pair found before any section header".to_string(),
+            Error::EmptySection { name, .. } => format!("section '{}' has no keys", name),
+            Error::InvalidInterpolation { chain } => {
+                format!("invalid interpolation syntax in {}", chain.join(" -> "))
+            }
+            Error::MissingReference { chain } => {
+                format!("reference to missing key in {}", chain.join(" -> "))
+            }
+            Error::InterpolationCycle { chain } => {
+                format!("interpolation cycle {}", chain.join(" -> "))
+            }
+            Error::InterpolationDepth { chain } => {
+                format!("interpolation too deeply nested in {}", chain.join(" -> "))
+            }
+            Error::UndefinedVariable {
+                section,
+                key,
+                variable,
+            } => format!(
+                "undefined variable '{}' in key '{}' of section '{}'",
+                variable, key, section
+            ),
+            Error::InvalidVariable { section, key } => format!(
+                "invalid variable reference in key '{}' of section '{}'",
+                key, section
+            ),
+            Error::InvalidValue {
+                section: Some(section),
+                key,
+                value,
+                target,
+            } => format!(
+                "value '{}' of key '{}' in section '{}' is not a valid {}",
+                value, key, section, target
+            ),
+            Error::InvalidValue {
+                section: None,
+                key,
+                value,
+                target,
+            } => format!(
+                "value '{}' of key '{}' is not a valid {}",
+                value, key, target
+            ),
+            Error::MissingKey {
+                section: Some(section),
+                key,
+            } => format!("missing key '{}' in section '{}'", key, section),
+            Error::MissingKey { section: None, key } => format!("missing key '{}'", key),
+            Error::Constraint {
+                section: Some(section),
+                key,
+                value,
+                message,
+            } => format!(
+                "value '{}' of key '{}' in section '{}' {}",
+                value, key, section, message
+            ),
+            Error::Constraint {
+                section: None,
+                key,
+                value,
+                message,
+            } => format!("value '{}' of key '{}' {}", value, key, message),
+            Error::MissingParent {
+                section, parent, ..
+            } => format!(
+                "section '{}' inherits from missing section '{}'",
+                section, parent
+            ),
+            Error::InheritanceCycle { chain, .. } => {
+                format!("inheritance cycle {}", chain.join(" -> "))
+            }
+            Error::InvalidInclude(_) => "invalid include directive".to_string(),
+            Error::IncludeCycle { chain, .. } => format!(
+                "include cycle {}",
+                chain
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            ),
+            Error::InvalidSchema {
+                section: Some(section),
+                message,
+            } => format!("invalid schema rule in section '{}': {}", section, message),
+            Error::InvalidSchema {
+                section: None,
+                message,
+            } => format!("invalid schema rule: {}", message),
+            Error::Serde {
+                section: Some(section),
+                key: Some(key),
+                message,
+            } => format!("{} for key '{}' in section '{}'", message, key, section),
+            Error::Serde {
+                section: Some(section),
+                key: None,
+                message,
+            } => format!("{} in section '{}'", message, section),
+            Error::Serde { message, .. } => message.clone(),
+            Error::InvalidName(name) => {
+                format!("'{}' is not a valid section name or key", name)
+            }
+            Error::Io { source, .. } => format!("unable to read cfg: {}", source),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io {
+                path: Some(path), ..
+            } => write!(f, "{}: {}", path.display(), self.message()),
+            _ => match self.location() {
+                Some(location) => write!(f, "{}: {}", location, self.message()),
+                None => write!(f, "{}", self.message()),
+            },
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/error.rs"]
+mod unit_tests;
@@ -0,0 +1,168 @@
+//! include
+//!
+//! This module assembles a cfg from a file on disk, along with any files
+//! it pulls in via include directives:
+//!
+//! ```notrust
+//! %include base.cfg
+//! @include conf.d/*.cfg
+//! ```
+//!
+//! Relative paths are resolved against the directory of the including
+//! file, and glob patterns include every matching file in sorted order. The
+//! text of an included file is treated as if it appeared in place of the
+//! directive. Each line of the assembled text remembers the file and line
+//! it came from, so that errors and values may be traced back to them.
+use crate::error::{Error, Location};
+use crate::{Config, Origin, ParserOptions};
+use std::path::{Path, PathBuf};
+
+const DIRECTIVES: [&str; 2] = ["%include", "@include"];
+
+// The text of a cfg, along with where each of its lines came from
+pub(crate) struct Assembled {
+    pub(crate) text: String,
+    origins: Vec<Origin>,
+}
+
+impl Assembled {
+    // Read the cfg at the supplied path, expanding include directives if the
+    // options permit them
+    pub(crate) fn from_path(cfg_path: &Path, opts: &ParserOptions) -> Result<Self, Error> {
+        let mut assembled = Self {
+            text: String::new(),
+            origins: Vec::new(),
+        };
+        let mut stack = Vec::new();
+        assembled.splice(cfg_path, opts.includes, &mut stack)?;
+        Ok(assembled)
+    }
+
+    // Point the location of an error at the file and line it came from
+    pub(crate) fn locate(&self, mut error: Error) -> Error {
+        if let Some(location) = error.location_mut() {
+            if let Some(origin) = self.origins.get(location.line.wrapping_sub(1)) {
+                location.path = origin.path.clone();
+                location.line = origin.line;
+            }
+        }
+        error
+    }
+
+    // Point the origins of the config's values at the files and lines they came from
+    pub(crate) fn locate_values(&self, config: &mut Config<'_>) {
+        for section in config.sections_mut() {
+            for origin in section.origins_mut() {
+                if let Some(source) = self.origins.get(origin.line.wrapping_sub(1)) {
+                    *origin = source.clone();
+                }
+            }
+        }
+    }
+
+    // Append the lines of the cfg at the supplied path. The stack holds the
+    // canonical paths of the files currently being included, in order to
+    // detect cycles.
+    fn splice(
+        &mut self,
+        cfg_path: &Path,
+        includes: bool,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<(), Error> {
+        let contents = std::fs::read_to_string(cfg_path).map_err(|source| Error::Io {
+            path: Some(cfg_path.to_path_buf()),
+            source,
+        })?;
+        if includes {
+            stack.push(canonical(cfg_path)?);
+        }
+        for (idx, line) in contents.lines().enumerate() {
+            let line_no = idx + 1;
+            match directive(line).filter(|_| includes) {
+                Some(pattern) => {
+                    let mut location = Location::new(line_no, line, indent(line))
+                        .with_length(line.trim().chars().count());
+                    location.path = Some(cfg_path.to_path_buf());
+                    for target in resolve(cfg_path, pattern, &location)? {
+                        let target_canonical = canonical(&target)?;
+                        if stack.contains(&target_canonical) {
+                            let mut chain = stack.clone();
+                            chain.push(target_canonical);
+                            return Err(Error::IncludeCycle { chain, location });
+                        }
+                        self.splice(&target, includes, stack)?;
+                    }
+                }
+                None => {
+                    if !self.origins.is_empty() {
+                        self.text.push('\n');
+                    }
+                    self.text.push_str(line);
+                    self.origins.push(Origin {
+                        path: Some(cfg_path.to_path_buf()),
+                        line: line_no,
+                    });
+                }
+            }
+        }
+        if includes {
+            stack.pop();
+        }
+        Ok(())
+    }
+}
+
+// the pattern following an include directive, if the line holds one
+fn directive(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+    DIRECTIVES.iter().find_map(|directive| {
+        let rest = trimmed.strip_prefix(directive)?;
+        if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+            Some(rest.trim())
+        } else {
+            None
+        }
+    })
+}
+
+// the files named by an include pattern, relative to the including file
+fn resolve(cfg_path: &Path, pattern: &str, location: &Location) -> Result<Vec<PathBuf>, Error> {
+    if pattern.is_empty() {
+        return Err(Error::InvalidInclude(location.clone()));
+    }
+    let target = match cfg_path.parent() {
+        Some(dir) => dir.join(pattern),
+        None => PathBuf::from(pattern),
+    };
+    if !pattern.contains(['*', '?', '[']) {
+        return Ok(vec![target]);
+    }
+    let target = target
+        .to_str()
+        .ok_or_else(|| Error::InvalidInclude(location.clone()))?;
+    let mut matches = glob::glob(target)
+        .map_err(|_| Error::InvalidInclude(location.clone()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| Error::Io {
+            path: Some(e.path().to_path_buf()),
+            source: e.into(),
+        })?;
+    matches.sort();
+    Ok(matches)
+}
+
+fn canonical(path: &Path) -> Result<PathBuf, Error> {
+    path.canonicalize().map_err(|source| Error::Io {
+        path: Some(path.to_path_buf()),
+        source,
+    })
+}
+
+// byte offset of the first non whitespace character in the line
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/include.rs"]
+mod unit_tests;
@@ -0,0 +1,194 @@
+//! interpolation
+//!
+//! This module houses Interpolator, which expands references to other
+//! keys within a value. Two syntaxes are supported, mirroring Python's
+//! configparser:
+//!
+//! ```notrust
+//! [cent7_64]
+//! architecture = linux_cent7_x86_64
+//! # Interpolation::Extended
+//! version = ${architecture}
+//! legacy = ${cent6_64:architecture}
+//! # Interpolation::Basic
+//! version = %(architecture)s
+//! ```
+//!
+//! References are looked up using the same rules as Config::get_value, so
+//! keys in a defaults section may be referenced from any section.
+use crate::error::Error;
+
+/// The syntax used to reference other keys within a value
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Interpolation {
+    /// `%(key)s` references a key in the same section. A literal '%' is
+    /// written as `%%`.
+    Basic,
+    /// `${key}` references a key in the same section, and `${section:key}`
+    /// a key in another section. A literal '$' is written as `$$`.
+    Extended,
+}
+
+/// Expands references to other keys within values.
+///
+/// # Example
+///
+/// ```
+/// use cfgparser::{Config, Interpolation, Interpolator};
+///
+/// let contents = r#"
+/// [cent7_64]
+/// architecture = linux_cent7_x86_64
+/// version = ${architecture}
+/// legacy = ${cent6_64:architecture}
+///
+/// [cent6_64]
+/// architecture = linux_cent6_x86_64
+/// "#;
+///
+/// let config = Config::parse_cfg_from_str(contents).unwrap();
+/// let interpolator = Interpolator::new(Interpolation::Extended);
+/// let legacy = config.get_interpolated("cent7_64", "legacy", &interpolator).unwrap();
+/// assert_eq!(legacy, Some("linux_cent6_x86_64".to_string()));
+/// // the raw value is still available
+/// assert_eq!(config.get_value("cent7_64", "legacy"), Some("${cent6_64:architecture}"));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Interpolator {
+    syntax: Interpolation,
+    max_depth: usize,
+}
+
+impl Interpolator {
+    /// Create a new Interpolator using the supplied syntax. References may be
+    /// nested up to 10 deep, as in Python's configparser.
+    pub fn new(syntax: Interpolation) -> Self {
+        Self {
+            syntax,
+            max_depth: 10,
+        }
+    }
+
+    /// Set the maximum number of references which may be followed while
+    /// expanding a single value.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    // Expand the value of the key in the section, given a function to look up
+    // raw values. Returns None if the key is not found.
+    pub(crate) fn interpolate<'v, F>(
+        &self,
+        lookup: F,
+        section: &str,
+        key: &str,
+    ) -> Result<Option<String>, Error>
+    where
+        F: Fn(&str, &str) -> Option<&'v str>,
+    {
+        let value = match lookup(section, key) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let mut chain = vec![link(section, key)];
+        self.expand(&lookup, section, value, &mut chain).map(Some)
+    }
+
+    // Expand the references in a value. The chain holds the keys visited on
+    // the way to the value, ending with the key the value belongs to.
+    fn expand<'v, F>(
+        &self,
+        lookup: &F,
+        section: &str,
+        value: &str,
+        chain: &mut Vec<String>,
+    ) -> Result<String, Error>
+    where
+        F: Fn(&str, &str) -> Option<&'v str>,
+    {
+        let (marker, open, close) = match self.syntax {
+            Interpolation::Basic => ('%', "(", ")s"),
+            Interpolation::Extended => ('$', "{", "}"),
+        };
+        let mut expanded = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(idx) = rest.find(marker) {
+            expanded.push_str(&rest[..idx]);
+            rest = &rest[idx + marker.len_utf8()..];
+            if rest.starts_with(marker) {
+                expanded.push(marker);
+                rest = &rest[marker.len_utf8()..];
+                continue;
+            }
+            let reference = rest
+                .strip_prefix(open)
+                .and_then(|inner| inner.find(close).map(|end| &inner[..end]))
+                .filter(|reference| !reference.is_empty())
+                .ok_or_else(|| Error::InvalidInterpolation {
+                    chain: chain.clone(),
+                })?;
+            rest = &rest[open.len() + reference.len() + close.len()..];
+
+            let (ref_section, ref_key) = match self.syntax {
+                Interpolation::Basic => (section, reference),
+                Interpolation::Extended => {
+                    let mut parts = reference.split(':');
+                    match (parts.next(), parts.next(), parts.next()) {
+                        (Some(key), None, None) => (section, key),
+                        (Some(section), Some(key), None) => (section, key),
+                        _ => {
+                            return Err(Error::InvalidInterpolation {
+                                chain: chain.clone(),
+                            })
+                        }
+                    }
+                }
+            };
+            expanded.push_str(&self.follow(lookup, ref_section, ref_key, chain)?);
+        }
+        expanded.push_str(rest);
+        Ok(expanded)
+    }
+
+    // Follow a reference to a key, returning its expanded value
+    fn follow<'v, F>(
+        &self,
+        lookup: &F,
+        section: &str,
+        key: &str,
+        chain: &mut Vec<String>,
+    ) -> Result<String, Error>
+    where
+        F: Fn(&str, &str) -> Option<&'v str>,
+    {
+        let next = link(section, key);
+        let cycle = chain.contains(&next);
+        chain.push(next);
+        if cycle {
+            return Err(Error::InterpolationCycle {
+                chain: chain.clone(),
+            });
+        }
+        if chain.len() > self.max_depth {
+            return Err(Error::InterpolationDepth {
+                chain: chain.clone(),
+            });
+        }
+        let value = lookup(section, key).ok_or_else(|| Error::MissingReference {
+            chain: chain.clone(),
+        })?;
+        let expanded = self.expand(lookup, section, value, chain)?;
+        chain.pop();
+        Ok(expanded)
+    }
+}
+
+// the name of a key within a chain of references
+fn link(section: &str, key: &str) -> String {
+    format!("{}:{}", section, key)
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/interpolation.rs"]
+mod unit_tests;
@@ -0,0 +1,145 @@
+//! layered
+//!
+//! This module houses LayeredConfig, which holds a stack of configs, such
+//! as a system, a site and a user config, and looks up keys through them
+//! with later layers taking precedence. A layer may remove keys and
+//! sections supplied by earlier layers via tombstones. See
+//! ParserOptions::tombstones.
+use crate::view::Items;
+use crate::{from_path_with, ConfigOwned, Error, Origin, ParserOptions};
+use std::path::Path;
+
+/// Controls how ConfigOwned::merge combines two configs
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MergeStrategy {
+    /// Keys in the later config replace keys of the same name, key by key
+    Override,
+    /// Keys in the later config are only added when the earlier config does
+    /// not already define them
+    Preserve,
+    /// Sections in the later config replace sections of the same name whole
+    ReplaceSections,
+}
+
+/// A value found in a LayeredConfig, along with where it came from
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct LayeredValue<'c> {
+    /// The value
+    pub value: &'c str,
+    /// The name of the layer which supplied the value
+    pub layer: &'c str,
+    /// Where the value was defined within the layer, if known
+    pub origin: Option<&'c Origin>,
+}
+
+/// A stack of configs, in which later layers take precedence over earlier
+/// ones, key by key.
+///
+/// # Example
+///
+/// ```
+/// use cfgparser::{Config, LayeredConfig, ParserOptions};
+///
+/// let opts = ParserOptions::new().tombstones(true);
+/// let system = Config::parse_with("[os]\nbits = 64\nstatus = Current\n", &opts).unwrap();
+/// let user = Config::parse_with("[os]\nbits = 32\n!status\n", &opts).unwrap();
+///
+/// let mut layers = LayeredConfig::new();
+/// layers.push("system", system.to_owned());
+/// layers.push("user", user.to_owned());
+///
+/// let bits = layers.lookup("os", "bits").unwrap();
+/// assert_eq!(bits.value, "32");
+/// assert_eq!(bits.layer, "user");
+/// assert_eq!(layers.get_value("os", "status"), None);
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct LayeredConfig {
+    layers: Vec<(String, ConfigOwned)>,
+}
+
+impl LayeredConfig {
+    /// Create a new, empty, instance of LayeredConfig
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a layer, taking precedence over every layer added before it
+    pub fn push<N>(&mut self, name: N, config: ConfigOwned)
+    where
+        N: Into<String>,
+    {
+        self.layers.push((name.into(), config));
+    }
+
+    /// Read the cfg at the supplied path, and add it as a layer named after
+    /// the path. See from_path_with.
+    pub fn push_path<P>(&mut self, cfg_path: P, opts: &ParserOptions) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let cfg_path = cfg_path.as_ref();
+        let config = from_path_with(cfg_path, opts)?;
+        self.push(cfg_path.display().to_string(), config);
+        Ok(())
+    }
+
+    /// Iterate over the names and configs of the layers, from the earliest
+    /// to the latest
+    pub fn layers(&self) -> impl Iterator<Item = (&str, &ConfigOwned)> {
+        self.layers
+            .iter()
+            .map(|(name, config)| (name.as_str(), config))
+    }
+
+    /// Retrieve a layer given its name
+    pub fn layer(&self, name: &str) -> Option<&ConfigOwned> {
+        self.layers
+            .iter()
+            .find(|(layer, _)| layer == name)
+            .map(|(_, config)| config)
+    }
+
+    /// Look up a key within a section, starting with the latest layer, and
+    /// reporting which layer supplied the value. The search stops at a layer
+    /// which removes the key, or which replaces the whole section.
+    pub fn lookup(&self, section: &str, key: &str) -> Option<LayeredValue<'_>> {
+        for (layer, config) in self.layers.iter().rev() {
+            let found = match config.section(section) {
+                Some(found) => found,
+                None => continue,
+            };
+            let key = config.fold_key(key);
+            if let Some((key, value)) = found.item(&key) {
+                return Some(LayeredValue {
+                    value,
+                    layer,
+                    origin: found.origin(key),
+                });
+            }
+            if found.replaces() || found.removed_keys().iter().any(|k| *k == key) {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// Look up the value of a key within a section. See lookup.
+    pub fn get_value(&self, section: &str, key: &str) -> Option<&str> {
+        self.lookup(section, key).map(|found| found.value)
+    }
+
+    /// Flatten the layers into a single config, merging each layer over the
+    /// ones before it with MergeStrategy::Override.
+    pub fn merged(&self) -> ConfigOwned {
+        let mut merged = ConfigOwned::new();
+        for (_, config) in self.layers.iter() {
+            merged.merge(config.clone(), MergeStrategy::Override);
+        }
+        merged
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/layered.rs"]
+mod unit_tests;
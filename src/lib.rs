@@ -16,6 +16,10 @@
 //! The differ from Toml in that the values are implicitly strings.
 //! Furthermore, they do not support nesting.
 //!
+//! Values containing spaces or other special characters may be wrapped in
+//! double or single quotes, within which escape sequences such as `\"`,
+//! `\n` and `\u{263a}` are recognized.
+//!
 //! The parser generates a structure that is a thin wrapper around
 //! a map of maps. There are two main custom structs provided for this:
 //!
@@ -31,20 +35,97 @@
 //! ```notrust
 //! parse_from_path
 //! ```  
+//!
+//! Problems encountered while parsing are reported via Error, which
+//! records the line and column at which the problem was found. An Error
+//! may be converted into a Diagnostic for display to a user.
 pub mod parser;
-pub use parser::*;
+pub use parser::parse_cfg_from_str;
 
 mod section;
+pub use section::Origin;
 pub use section::Section;
+pub use section::SectionEntry;
+pub use section::SectionIter;
 pub use section::SectionOwned;
 
 mod config;
 pub use config::Config;
 pub use config::ConfigOwned;
 
+mod view;
+pub use view::SectionView;
+pub use view::ViewItem;
+
+mod env;
+pub use env::Environment;
+pub use env::VariableSource;
+
+mod include;
+
+mod typed;
+pub use typed::FromValue;
+
+mod checks;
+
+#[doc(hidden)]
+pub mod derive;
+
+mod interpolation;
+pub use interpolation::Interpolation;
+pub use interpolation::Interpolator;
+
+mod layered;
+pub use layered::LayeredConfig;
+pub use layered::LayeredValue;
+pub use layered::MergeStrategy;
+
+mod options;
+pub use options::Continuation;
+pub use options::Dialect;
+pub use options::DuplicatePolicy;
+pub use options::Names;
+pub use options::ParserOptions;
+
+mod writer;
+pub use writer::FormatOptions;
+pub use writer::Quoting;
+
+mod document;
+pub use document::Document;
+
+mod schema;
+pub use schema::Rule;
+pub use schema::Schema;
+pub use schema::ValueType;
+pub use schema::Violation;
+
+#[cfg(feature = "serde")]
+pub mod de;
+#[cfg(feature = "serde")]
+pub use de::from_file;
+#[cfg(feature = "serde")]
+pub use de::from_str;
+#[cfg(feature = "serde")]
+pub mod ser;
+#[cfg(feature = "serde")]
+pub use ser::to_string;
+
+#[cfg(feature = "derive")]
+pub use cfgparser_derive::CfgSection;
+
+mod error;
+pub use error::Error;
+pub use error::Location;
+
+mod diagnostic;
+pub use diagnostic::Diagnostic;
+pub use diagnostic::Style;
+
 use std::path::Path;
 
-/// Create a config from a path
+/// Create a config from a path. Any Error returned records the path
+/// of the cfg.
 ///
 /// # Example
 ///
@@ -58,11 +139,40 @@ use std::path::Path;
 /// # Ok(())
 /// # }
 /// ```
-pub fn from_path<P>(cfg_path: P) -> Result<ConfigOwned, Box<dyn std::error::Error>>
+pub fn from_path<P>(cfg_path: P) -> Result<ConfigOwned, Error>
+where
+    P: AsRef<Path>,
+{
+    from_path_with(cfg_path, &ParserOptions::default())
+}
+
+/// Create a config from a path, using the supplied ParserOptions to control
+/// the grammar. When ParserOptions::includes is set, include directives are
+/// expanded. Any Error returned, as well as the origin of each value, records
+/// the path and line of the file responsible.
+///
+/// # Example
+///
+/// ```
+/// use cfgparser::{from_path_with, ParserOptions};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut cfgpath = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+/// cfgpath.push("example_data");
+/// cfgpath.push("site.cfg");
+/// let config = from_path_with(cfgpath, &ParserOptions::new().includes(true))?;
+///
+/// let origin = config.origin("cent7_64", "bits").unwrap();
+/// assert!(origin.path.as_ref().unwrap().ends_with("operating_systems.cfg"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_path_with<P>(cfg_path: P, opts: &ParserOptions) -> Result<ConfigOwned, Error>
 where
     P: AsRef<Path>,
 {
-    let config_str = std::fs::read_to_string(cfg_path.as_ref())?;
-    let config = Config::parse_cfg_from_str(config_str.as_str())?;
+    let assembled = include::Assembled::from_path(cfg_path.as_ref(), opts)?;
+    let mut config =
+        Config::parse_with(assembled.text.as_str(), opts).map_err(|e| assembled.locate(e))?;
+    assembled.locate_values(&mut config);
     Ok(config.to_owned())
 }
@@ -0,0 +1,496 @@
+//! options
+//!
+//! This module houses ParserOptions, which controls the grammar accepted
+//! when parsing a cfg. The default options accept the strict grammar
+//! described at the root of the crate. Presets for other common dialects
+//! are available via Dialect.
+
+/// Controls whether, and how, a value may be continued over several lines.
+/// Continued values are joined with newlines.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Continuation {
+    /// Every value fits on a single line
+    Disabled,
+    /// A line indented further than the key preceding it continues that key's
+    /// value, as in Python's configparser. Blank lines are kept within the
+    /// value when a further indented line follows them, while comment lines
+    /// are skipped.
+    Indented,
+    /// A value ending in a backslash is continued on the next line
+    Backslash,
+}
+
+/// Controls which section names and keys are accepted
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Names {
+    /// Names start with a letter, followed by letters, numbers and single
+    /// underscores
+    Strict,
+    /// As in Python's configparser, a section name is anything between the
+    /// brackets of its header, and a key is anything before the delimiter.
+    /// Surrounding whitespace is trimmed, so names such as `My Section`,
+    /// `my-key` and `foo.bar` are accepted.
+    Permissive,
+}
+
+/// Controls what happens when a section, or a key within a section,
+/// appears more than once.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DuplicatePolicy {
+    /// Report the duplicate as an Error
+    Error,
+    /// Ignore every occurrence after the first
+    KeepFirst,
+    /// Each occurrence replaces the one before it
+    KeepLast,
+    /// For sections, combine the keys of every occurrence, with later keys
+    /// replacing earlier ones. For keys, this is the same as KeepLast.
+    Merge,
+}
+
+/// Named presets for ParserOptions
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Dialect {
+    /// The grammar accepted by default. Values are single words, only '='
+    /// delimits keys from values, only '#' starts a comment, and sections may
+    /// not be repeated.
+    Strict,
+    /// Matches the defaults of Python's configparser. Both '=' and ':' are
+    /// delimiters, '#' and ';' start comments, names are permissive, values
+    /// may contain spaces and continue on indented lines, keys are folded to
+    /// lower case, and neither sections nor keys may be repeated. Keys in the `[DEFAULT]` section are
+    /// visible from every other section.
+    Python,
+    /// Matches Windows ini files. Only ';' starts a comment, names are
+    /// permissive, values may contain spaces, keys are folded to lower case,
+    /// repeated sections are merged, and the first occurrence of a key wins.
+    WindowsIni,
+}
+
+/// Options controlling how a cfg is parsed.
+///
+/// # Example
+///
+/// ```
+/// use cfgparser::{Config, ParserOptions, Section};
+///
+/// let opts = ParserOptions::new().spaces_in_values(true);
+/// let config = Config::parse_with("[os]\nlabel = Linux CentOS 7 (64 bit)  \n", &opts).unwrap();
+///
+/// let mut expected = Section::new("os");
+/// expected.insert("label", "Linux CentOS 7 (64 bit)");
+/// assert_eq!(config.section("os"), Some(&expected));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParserOptions {
+    pub(crate) delimiters: Vec<String>,
+    pub(crate) spaces_in_values: bool,
+    pub(crate) comment_prefixes: Vec<String>,
+    pub(crate) inline_comment_prefixes: Vec<String>,
+    pub(crate) continuation: Continuation,
+    pub(crate) names: Names,
+    pub(crate) empty_sections: bool,
+    pub(crate) allow_no_value: bool,
+    pub(crate) fold_case: bool,
+    pub(crate) duplicate_sections: DuplicatePolicy,
+    pub(crate) duplicate_keys: DuplicatePolicy,
+    pub(crate) global_section: Option<String>,
+    pub(crate) default_section: Option<String>,
+    pub(crate) includes: bool,
+    pub(crate) tombstones: bool,
+    pub(crate) inheritance: bool,
+    #[cfg(feature = "serde")]
+    pub(crate) list_separator: String,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self::dialect(Dialect::Strict)
+    }
+}
+
+impl From<Dialect> for ParserOptions {
+    fn from(dialect: Dialect) -> Self {
+        Self::dialect(dialect)
+    }
+}
+
+impl ParserOptions {
+    /// Create a new instance of ParserOptions, accepting the strict grammar
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new instance of ParserOptions from one of the named presets.
+    /// The preset may be further customized using the builder methods.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, Dialect, ParserOptions};
+    ///
+    /// let contents = r#"
+    /// [Cent7_64]
+    /// Label: Linux CentOS 7 (64 bit)
+    /// ; retired hosts are listed below
+    /// hosts = lichost1
+    ///     lichost2
+    ///
+    /// [future_os]
+    /// "#;
+    ///
+    /// let opts = ParserOptions::dialect(Dialect::Python);
+    /// let config = Config::parse_with(contents, &opts).unwrap();
+    /// assert!(config.get("future_os").is_some());
+    /// ```
+    pub fn dialect(dialect: Dialect) -> Self {
+        let strict = Self {
+            delimiters: vec!["=".to_string()],
+            spaces_in_values: false,
+            comment_prefixes: vec!["#".to_string()],
+            inline_comment_prefixes: Vec::new(),
+            continuation: Continuation::Disabled,
+            names: Names::Strict,
+            empty_sections: true,
+            allow_no_value: false,
+            fold_case: false,
+            duplicate_sections: DuplicatePolicy::Error,
+            duplicate_keys: DuplicatePolicy::KeepLast,
+            global_section: None,
+            default_section: None,
+            includes: false,
+            tombstones: false,
+            inheritance: false,
+            #[cfg(feature = "serde")]
+            list_separator: ",".to_string(),
+        };
+        match dialect {
+            Dialect::Strict => strict,
+            Dialect::Python => strict
+                .delimiters(vec!["=", ":"])
+                .comment_prefixes(vec!["#", ";"])
+                .names(Names::Permissive)
+                .spaces_in_values(true)
+                .continuation(Continuation::Indented)
+                .fold_case(true)
+                .duplicate_keys(DuplicatePolicy::Error)
+                .default_section("DEFAULT"),
+            Dialect::WindowsIni => strict
+                .comment_prefixes(vec![";"])
+                .names(Names::Permissive)
+                .spaces_in_values(true)
+                .fold_case(true)
+                .duplicate_sections(DuplicatePolicy::Merge)
+                .duplicate_keys(DuplicatePolicy::KeepFirst),
+        }
+    }
+
+    /// Set the delimiters which may separate a key from its value. Defaults
+    /// to `=`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, ParserOptions};
+    ///
+    /// let opts = ParserOptions::new().delimiters(vec!["=", ":"]);
+    /// let config = Config::parse_with("[os]\nbits: 64\ntype = LINUX\n", &opts).unwrap();
+    /// assert!(config.get("os").is_some());
+    /// ```
+    pub fn delimiters<I, S>(mut self, delimiters: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.delimiters = delimiters.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// When true, an unquoted value runs to the end of the line, and may
+    /// contain spaces and other characters which are otherwise illegal.
+    /// Trailing whitespace is trimmed. This matches the behavior of
+    /// Python's configparser and Windows ini files.
+    pub fn spaces_in_values(mut self, allow: bool) -> Self {
+        self.spaces_in_values = allow;
+        self
+    }
+
+    /// Set the prefixes which mark a line, ignoring leading whitespace, as a
+    /// comment. Defaults to `#`. Equivalent to configparser's `comment_prefixes`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, ParserOptions};
+    ///
+    /// let opts = ParserOptions::new().comment_prefixes(vec!["#", ";"]);
+    /// let config = Config::parse_with("; generated\n[os]\nbits = 64\n", &opts).unwrap();
+    /// assert!(config.get("os").is_some());
+    /// ```
+    pub fn comment_prefixes<I, S>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.comment_prefixes = prefixes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the prefixes which start a comment following a header or value.
+    /// Within a value, a prefix only starts a comment when preceded by
+    /// whitespace, and never within quotes. Defaults to none. Equivalent to
+    /// configparser's `inline_comment_prefixes`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, ParserOptions, Section};
+    ///
+    /// let opts = ParserOptions::new().inline_comment_prefixes(vec!["#", ";"]);
+    /// let config = Config::parse_with("[os]\nbits = 64   # or 32\n", &opts).unwrap();
+    ///
+    /// let mut expected = Section::new("os");
+    /// expected.insert("bits", "64");
+    /// assert_eq!(config.section("os"), Some(&expected));
+    /// ```
+    pub fn inline_comment_prefixes<I, S>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.inline_comment_prefixes = prefixes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set whether values may be continued over several lines. Defaults to
+    /// Continuation::Disabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, Continuation, ParserOptions, Section};
+    ///
+    /// let contents = "[site]\nhosts = lichost1\n    lichost2\n    lichost3\n";
+    /// let opts = ParserOptions::new().continuation(Continuation::Indented);
+    /// let config = Config::parse_with(contents, &opts).unwrap();
+    ///
+    /// let mut expected = Section::new("site");
+    /// expected.insert("hosts", "lichost1\nlichost2\nlichost3".to_string());
+    /// assert_eq!(config.section("site"), Some(&expected));
+    /// ```
+    pub fn continuation(mut self, continuation: Continuation) -> Self {
+        self.continuation = continuation;
+        self
+    }
+
+    /// Set which section names and keys are accepted. Defaults to
+    /// Names::Strict.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, Names, ParserOptions};
+    ///
+    /// let opts = ParserOptions::new().names(Names::Permissive);
+    /// let config = Config::parse_with("[My App]\nlog-level = 2\n", &opts).unwrap();
+    /// assert_eq!(config.get_value("My App", "log-level"), Some("2"));
+    /// ```
+    pub fn names(mut self, names: Names) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// When false, each section header must be followed by at least one key.
+    /// Defaults to true.
+    pub fn empty_sections(mut self, allow: bool) -> Self {
+        self.empty_sections = allow;
+        self
+    }
+
+    /// When true, a key may appear on a line by itself, without a delimiter
+    /// or value. Such keys are given an empty value. Defaults to false.
+    pub fn allow_no_value(mut self, allow: bool) -> Self {
+        self.allow_no_value = allow;
+        self
+    }
+
+    /// When true, keys are folded to lower case, so that `Bits` and `bits`
+    /// name the same key. Section names are left alone. The resulting Config
+    /// folds the keys it is asked for in the same way, so lookups such as
+    /// Config::get_value match regardless of case. Sections retrieved on
+    /// their own store the folded keys. Defaults to false.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, ParserOptions};
+    ///
+    /// let opts = ParserOptions::new().fold_case(true);
+    /// let config = Config::parse_with("[os]\nBits = 64\n", &opts).unwrap();
+    /// assert_eq!(config.get_value("os", "BITS"), Some("64"));
+    /// assert_eq!(config.get_int("os", "Bits").unwrap(), Some(64));
+    /// ```
+    pub fn fold_case(mut self, fold: bool) -> Self {
+        self.fold_case = fold;
+        self
+    }
+
+    /// Set what happens when a section appears more than once. Defaults to
+    /// DuplicatePolicy::Error.
+    pub fn duplicate_sections(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_sections = policy;
+        self
+    }
+
+    /// Set what happens when a key appears more than once within a section.
+    /// Defaults to DuplicatePolicy::KeepLast.
+    pub fn duplicate_keys(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Collect any keys appearing before the first section header into an
+    /// implicit section with the supplied name, rather than reporting them as
+    /// an error. The section is available via Config::globals, as well as by
+    /// name. A later header with the same name is treated as a duplicate of
+    /// the implicit section.
+    pub fn global_section<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.global_section = Some(name.into());
+        self
+    }
+
+    /// Designate the section with the supplied name as holding defaults for
+    /// every other section, as with the `[DEFAULT]` section of Python's
+    /// configparser. Lookups made through Config::get and Config::get_value
+    /// fall back to the defaults section when a section does not define a
+    /// key. Config::section returns only the keys a section defines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, ParserOptions};
+    ///
+    /// let contents = "[DEFAULT]\nbits = 64\n\n[cent7_64]\ntype = LINUX\n";
+    /// let opts = ParserOptions::new().default_section("DEFAULT");
+    /// let config = Config::parse_with(contents, &opts).unwrap();
+    /// assert_eq!(config.get_value("cent7_64", "bits"), Some("64"));
+    /// assert_eq!(config.get_value("cent7_64", "type"), Some("LINUX"));
+    /// ```
+    pub fn default_section<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.default_section = Some(name.into());
+        self
+    }
+
+    /// When true, a line holding `%include path` or `@include path` is
+    /// replaced by the contents of the named file. Relative paths are
+    /// resolved against the directory of the including file, and glob
+    /// patterns such as `conf.d/*.cfg` include every matching file in sorted
+    /// order. Includes are only expanded when reading a cfg via
+    /// from_path_with. Defaults to false.
+    pub fn includes(mut self, allow: bool) -> Self {
+        self.includes = allow;
+        self
+    }
+
+    /// When true, a header such as `[!name]` declares a section which replaces
+    /// the section of the same name in earlier layers, or removes it if no
+    /// keys follow. Within a section, a line such as `!key` removes the key
+    /// from earlier layers. See ConfigOwned::merge and LayeredConfig.
+    /// Defaults to false.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::{Config, ParserOptions};
+    ///
+    /// let contents = "[!cent6_64]\n\n[cent7_64]\n!legacy\n";
+    /// let opts = ParserOptions::new().tombstones(true);
+    /// let config = Config::parse_with(contents, &opts).unwrap();
+    /// assert!(config.section("cent6_64").unwrap().replaces());
+    /// assert_eq!(config.section("cent7_64").unwrap().removed_keys(), ["legacy"]);
+    /// ```
+    pub fn tombstones(mut self, allow: bool) -> Self {
+        self.tombstones = allow;
+        self
+    }
+
+    /// When true, a section may declare a parent, either in its header, as
+    /// in `[cent7_64 : cent6_64]`, or via an `inherits = cent6_64` key. The
+    /// section inherits every key of its parent which it does not define
+    /// itself, and the parent may in turn have a parent of its own. Every
+    /// parent must exist, and a section may not inherit from itself, directly
+    /// or otherwise. Inherited keys are visible via Config::get.
+    /// Defaults to false.
+    pub fn inheritance(mut self, allow: bool) -> Self {
+        self.inheritance = allow;
+        self
+    }
+
+    /// Set the text splitting a value into the items of a sequence, such as
+    /// a Vec, when deserializing with the `serde` feature. Defaults to `","`,
+    /// matching FormatOptions::list_separator.
+    #[cfg(feature = "serde")]
+    pub fn list_separator<S>(mut self, separator: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.list_separator = separator.into();
+        self
+    }
+
+    // is the supplied line, minus surrounding whitespace, a comment
+    pub(crate) fn is_comment(&self, trimmed: &str) -> bool {
+        self.comment_prefixes
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix.as_str()))
+    }
+
+    // does the supplied text, minus leading whitespace, start an inline comment
+    pub(crate) fn is_inline_comment(&self, text: &str) -> bool {
+        let text = text.trim_start();
+        self.inline_comment_prefixes
+            .iter()
+            .any(|prefix| text.starts_with(prefix.as_str()))
+    }
+
+    // byte offset of the whitespace preceding the first inline comment in the text
+    pub(crate) fn find_inline_comment(&self, text: &str) -> Option<usize> {
+        text.char_indices()
+            .find(|(idx, c)| c.is_whitespace() && self.is_inline_comment(&text[*idx..]))
+            .map(|(idx, _)| idx)
+    }
+
+    // strip the delimiter found at the start of the text, if there is one
+    pub(crate) fn strip_delimiter<'s>(&self, text: &'s str) -> Option<&'s str> {
+        self.delimiters
+            .iter()
+            .find(|delimiter| !delimiter.is_empty() && text.starts_with(delimiter.as_str()))
+            .map(|delimiter| &text[delimiter.len()..])
+    }
+
+    // byte offset of the first delimiter in the text
+    pub(crate) fn find_delimiter(&self, text: &str) -> Option<usize> {
+        self.delimiters
+            .iter()
+            .filter(|delimiter| !delimiter.is_empty())
+            .filter_map(|delimiter| text.find(delimiter.as_str()))
+            .min()
+    }
+
+    // does the text contain any of the delimiters
+    pub(crate) fn contains_delimiter(&self, text: &str) -> bool {
+        self.delimiters
+            .iter()
+            .any(|delimiter| !delimiter.is_empty() && text.contains(delimiter.as_str()))
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/options.rs"]
+mod unit_tests;
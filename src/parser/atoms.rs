@@ -7,9 +7,11 @@ use nom::character::complete::alphanumeric0;
 use nom::character::complete::alphanumeric1;
 use nom::character::complete::multispace0;
 use nom::combinator::recognize;
+use nom::error::ErrorKind;
 use nom::multi::many0;
 use nom::sequence::pair;
 use nom::IResult;
+use std::borrow::Cow;
 
 /// Parse a str that starts with a letter, followed by zero or more
 /// letters and/or numbers
@@ -113,6 +115,89 @@ pub fn space0_eol(input: &str) -> IResult<&str, &str> {
     ))(input)
 }
 
+/// Parse a value wrapped in double or single quotes, returning the value
+/// with its quotes removed and any escape sequences replaced. Values without
+/// escape sequences are borrowed from the input rather than copied.
+///
+/// The following escape sequences are supported: `\"`, `\'`, `\\`, `\n`,
+/// `\t`, `\r`, `\0` and `\u{...}`, where `...` is one to six hex digits.
+///
+/// An unknown escape sequence fails with `ErrorKind::Escaped`, positioned at
+/// the offending backslash. A missing closing quote fails with
+/// `ErrorKind::Eof`, positioned at the opening quote.
+///
+/// # Examples
+///
+/// ```
+/// use cfgparser::parser::atoms::quoted_value;
+/// use std::borrow::Cow;
+///
+/// let result = quoted_value(r#""Cent OS 7" # comment"#);
+/// assert_eq!(result, Ok((" # comment", Cow::Borrowed("Cent OS 7"))));
+///
+/// let result = quoted_value(r#"'it\'s \u{263a}'"#);
+/// assert_eq!(result, Ok(("", Cow::Owned("it's \u{263a}".to_string()))));
+/// ```
+pub fn quoted_value(input: &str) -> IResult<&str, Cow<'_, str>> {
+    let quote = match input.chars().next() {
+        Some(c) if c == '"' || c == '\'' => c,
+        _ => return Err(nom::Err::Error((input, ErrorKind::Char))),
+    };
+    let body = &input[1..];
+    // only allocate once we encounter an escape sequence
+    let mut owned: Option<String> = None;
+    let mut chars = body.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        if c == quote {
+            let value = match owned {
+                Some(value) => Cow::Owned(value),
+                None => Cow::Borrowed(&body[..idx]),
+            };
+            return Ok((&body[idx + 1..], value));
+        }
+        if c != '\\' {
+            if let Some(value) = owned.as_mut() {
+                value.push(c);
+            }
+            continue;
+        }
+        let bad_escape = || nom::Err::Failure((&body[idx..], ErrorKind::Escaped));
+        let unescaped = match chars.next().map(|(_, c)| c) {
+            Some('"') => '"',
+            Some('\'') => '\'',
+            Some('\\') => '\\',
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('0') => '\0',
+            Some('u') => unicode_escape(&mut chars).ok_or_else(bad_escape)?,
+            _ => return Err(bad_escape()),
+        };
+        owned
+            .get_or_insert_with(|| body[..idx].to_string())
+            .push(unescaped);
+    }
+    Err(nom::Err::Failure((input, ErrorKind::Eof)))
+}
+
+// parse the `{...}` following `\u`, returning the char it describes
+fn unicode_escape(chars: &mut std::str::CharIndices) -> Option<char> {
+    if chars.next()?.1 != '{' {
+        return None;
+    }
+    let mut code = String::new();
+    loop {
+        match chars.next()?.1 {
+            '}' => break,
+            c if c.is_ascii_hexdigit() && code.len() < 6 => code.push(c),
+            _ => return None,
+        }
+    }
+    u32::from_str_radix(&code, 16)
+        .ok()
+        .and_then(std::char::from_u32)
+}
+
 #[cfg(test)]
 #[path = "../unit_tests/parser_atoms.rs"]
 mod unit_tests;
@@ -0,0 +1,573 @@
+//! driver
+//!
+//! Walks a cfg a line at a time, handing each line to the nom parsers
+//! defined in `parser` and `parser::atoms`. Walking the lines ourselves,
+//! rather than handing the whole file to a single combinator, lets us
+//! report exactly which line failed, where, and why.
+use super::atoms::*;
+use super::{header, inherit_header, tombstone_header, until_illegal_char};
+use crate::error::{Error, Location};
+use crate::Section;
+use crate::{Continuation, DuplicatePolicy, Names, ParserOptions};
+use nom::character::complete::space0;
+use nom::error::ErrorKind;
+use nom::IResult;
+use std::borrow::Cow;
+
+// the key which, when inheritance is enabled, names the parent of a section
+pub(crate) const INHERITS: &str = "inherits";
+
+/// Parse a cfg into a list of sections, returning the first problem
+/// encountered as an Error.
+pub(crate) fn parse_lines<'a>(
+    input: &'a str,
+    opts: &ParserOptions,
+) -> Result<Vec<Section<'a>>, Error> {
+    let mut driver = Driver::new(opts, false);
+    driver.run(input)?;
+    Ok(driver.sections)
+}
+
+/// Parse a cfg into a list of sections, carrying on past any problems.
+/// Every problem encountered is returned, along with whichever sections
+/// could be parsed.
+pub(crate) fn parse_lines_recovering<'a>(
+    input: &'a str,
+    opts: &ParserOptions,
+) -> (Vec<Section<'a>>, Vec<Error>) {
+    let mut driver = Driver::new(opts, true);
+    // when recovering, errors are collected rather than returned
+    let _ = driver.run(input);
+    (driver.sections, driver.errors)
+}
+
+struct Driver<'a, 'o> {
+    opts: &'o ParserOptions,
+    recover: bool,
+    sections: Vec<Section<'a>>,
+    // the section which key value pairs are currently being added to
+    current: Option<Current>,
+    // set after a header we could not use, or a key outside of any section.
+    // lines are ignored until the next good header, rather than reporting
+    // each one, or attributing them to the wrong section.
+    skipping: bool,
+    // the most recent key value pair, held back until we know whether the
+    // following lines continue its value
+    pending: Option<Pending<'a>>,
+    // index of each section declaring a parent, along with the location of
+    // the declaration
+    parents: Vec<(usize, Location)>,
+    errors: Vec<Error>,
+}
+
+struct Current {
+    // index of the section within sections
+    index: usize,
+    // location of the section's header
+    location: Location,
+    has_keys: bool,
+}
+
+// a value which may be continued by the lines which follow it
+struct Pending<'a> {
+    key: Cow<'a, str>,
+    // line number of the key
+    line_no: usize,
+    // the key is a duplicate which should be dropped rather than inserted
+    discard: bool,
+    // indentation of the line holding the key
+    indent: usize,
+    value: Cow<'a, str>,
+    // the value ended with a backslash, so the next line continues it
+    escaped_newline: bool,
+    // blank lines seen since the value was last continued
+    blank_lines: usize,
+}
+
+impl<'a, 'o> Driver<'a, 'o> {
+    fn new(opts: &'o ParserOptions, recover: bool) -> Self {
+        Self {
+            opts,
+            recover,
+            sections: Vec::new(),
+            current: None,
+            skipping: false,
+            pending: None,
+            parents: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn run(&mut self, input: &'a str) -> Result<(), Error> {
+        for (idx, raw) in input.split('\n').enumerate() {
+            let line = raw.strip_suffix('\r').unwrap_or(raw);
+            if let Err(e) = self.line(idx + 1, line) {
+                self.fail(e)?;
+            }
+        }
+        self.flush();
+        let current = self.current.take();
+        if let Err(e) = self.check_not_empty(current) {
+            self.fail(e)?;
+        }
+        for e in self.check_parents() {
+            self.fail(e)?;
+        }
+        Ok(())
+    }
+
+    // either record the error and carry on, or hand it back to stop parsing
+    fn fail(&mut self, error: Error) -> Result<(), Error> {
+        if self.recover {
+            self.errors.push(error);
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    fn line(&mut self, line_no: usize, line: &'a str) -> Result<(), Error> {
+        if self.continues_value(line) {
+            self.continue_value(line);
+            return Ok(());
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            // as in Python's configparser, blank lines within an indented
+            // value are kept if a further continuation line follows them
+            if let Some(pending) = self.pending.as_mut() {
+                pending.blank_lines += 1;
+            }
+            return Ok(());
+        }
+        if self.opts.is_comment(trimmed) {
+            return Ok(());
+        }
+        self.flush();
+        if trimmed.starts_with('[') {
+            return self.header(line_no, line);
+        }
+        if self.skipping {
+            return Ok(());
+        }
+        let index = match self.current.as_mut() {
+            Some(current) => {
+                current.has_keys = true;
+                current.index
+            }
+            // keys before the first header belong to the implicit global section
+            None if self.sections.is_empty() && self.opts.global_section.is_some() => {
+                let name = self.opts.global_section.clone().unwrap_or_default();
+                self.sections.push(Section::new(name));
+                self.current = Some(Current {
+                    index: 0,
+                    location: Location::new(line_no, line, indent(line)),
+                    has_keys: true,
+                });
+                0
+            }
+            None => {
+                self.skipping = true;
+                return Err(Error::MissingHeader(
+                    Location::new(line_no, line, indent(line)).with_length(trimmed_len(line)),
+                ));
+            }
+        };
+        if self.opts.tombstones && trimmed.starts_with('!') {
+            let key = tombstone_line(line_no, line, self.opts)?;
+            let key = fold_key(key, self.opts.fold_case);
+            self.sections[index].remove_parsed(key);
+            return Ok(());
+        }
+        let (key, _, mut value) = key_value_line(line_no, line, self.opts)?;
+        let key = fold_key(key, self.opts.fold_case);
+        if self.opts.inheritance && key == INHERITS {
+            let location =
+                Location::new(line_no, line, indent(line)).with_length(trimmed_len(line));
+            self.set_parent(index, value, location);
+            return Ok(());
+        }
+        let section = &self.sections[index];
+        let discard = section.contains_key(&key)
+            && match self.opts.duplicate_keys {
+                DuplicatePolicy::Error => {
+                    return Err(Error::DuplicateKey {
+                        section: section.name().to_string(),
+                        key: key.into_owned(),
+                        location: Location::new(line_no, line, indent(line))
+                            .with_length(token_len(line.trim_start())),
+                    })
+                }
+                DuplicatePolicy::KeepFirst => true,
+                DuplicatePolicy::KeepLast | DuplicatePolicy::Merge => false,
+            };
+        // only an unquoted value may end in a backslash which continues it
+        let escaped_newline = self.opts.continuation == Continuation::Backslash
+            && line.trim_end().ends_with('\\')
+            && strip_trailing_backslash(&mut value);
+        self.pending = Some(Pending {
+            key,
+            line_no,
+            discard,
+            indent: indent(line),
+            value,
+            escaped_newline,
+            blank_lines: 0,
+        });
+        Ok(())
+    }
+
+    // does the supplied line continue the pending value
+    fn continues_value(&self, line: &str) -> bool {
+        let pending = match &self.pending {
+            Some(pending) => pending,
+            None => return false,
+        };
+        match self.opts.continuation {
+            Continuation::Backslash => pending.escaped_newline,
+            Continuation::Indented => {
+                let trimmed = line.trim();
+                !trimmed.is_empty()
+                    && !self.opts.is_comment(trimmed)
+                    && indent(line) > pending.indent
+            }
+            Continuation::Disabled => false,
+        }
+    }
+
+    // append the supplied line to the pending value
+    fn continue_value(&mut self, line: &'a str) {
+        let mut text = line.trim();
+        if let Some(end) = self.opts.find_inline_comment(text) {
+            text = text[..end].trim_end();
+        }
+        let mut text = Cow::Borrowed(text);
+        let escaped_newline = self.opts.continuation == Continuation::Backslash
+            && strip_trailing_backslash(&mut text);
+        if let Some(pending) = self.pending.as_mut() {
+            let value = pending.value.to_mut();
+            for _ in 0..pending.blank_lines {
+                value.push('\n');
+            }
+            value.push('\n');
+            value.push_str(&text);
+            pending.blank_lines = 0;
+            pending.escaped_newline = escaped_newline;
+        }
+    }
+
+    // insert the pending key value pair into the current section
+    fn flush(&mut self) {
+        if let (Some(pending), Some(current)) = (self.pending.take(), self.current.as_ref()) {
+            if !pending.discard {
+                self.sections[current.index].insert_parsed(
+                    pending.key,
+                    pending.value,
+                    pending.line_no,
+                );
+            }
+        }
+    }
+
+    fn header(&mut self, line_no: usize, line: &'a str) -> Result<(), Error> {
+        let previous = self.current.take();
+        if let Err(e) = self.check_not_empty(previous) {
+            self.fail(e)?;
+        }
+        self.skipping = true;
+        let invalid = |offset: usize| {
+            Error::InvalidHeader(
+                Location::new(line_no, line, offset).with_length(token_len(&line[offset..])),
+            )
+        };
+        let (name, parent, replaces) = match parse_header(line, self.opts) {
+            Ok((rest, parsed)) if rest.is_empty() || self.opts.is_inline_comment(rest) => parsed,
+            Ok((rest, _)) => return Err(invalid(line.len() - rest.len())),
+            Err(e) => return Err(invalid(error_offset(line, e))),
+        };
+        let location = Location::new(line_no, line, indent(line)).with_length(trimmed_len(line));
+        let existing = self.sections.iter().position(|s| s.name() == name);
+        let (index, has_keys) = match existing {
+            // a tombstone replaces any earlier section of the same name
+            _ if replaces => {
+                let section = Section::replacing(name);
+                match existing {
+                    Some(index) => {
+                        self.sections[index] = section;
+                        (index, false)
+                    }
+                    None => {
+                        self.sections.push(section);
+                        (self.sections.len() - 1, false)
+                    }
+                }
+            }
+            None => {
+                self.sections.push(Section::new(name));
+                (self.sections.len() - 1, false)
+            }
+            Some(index) => match self.opts.duplicate_sections {
+                DuplicatePolicy::Error => {
+                    return Err(Error::DuplicateSection {
+                        name: name.to_string(),
+                        location,
+                    })
+                }
+                // carry on skipping the section's lines
+                DuplicatePolicy::KeepFirst => return Ok(()),
+                DuplicatePolicy::KeepLast => {
+                    self.sections[index] = Section::new(name);
+                    (index, false)
+                }
+                DuplicatePolicy::Merge => (index, true),
+            },
+        };
+        if let Some(parent) = parent {
+            self.set_parent(index, Cow::Borrowed(parent), location.clone());
+        }
+        self.current = Some(Current {
+            index,
+            location,
+            has_keys,
+        });
+        self.skipping = false;
+        Ok(())
+    }
+
+    // record the parent of a section, replacing any declared earlier
+    fn set_parent(&mut self, index: usize, parent: Cow<'a, str>, location: Location) {
+        self.sections[index].set_parent(parent);
+        self.parents.retain(|(declared, _)| *declared != index);
+        self.parents.push((index, location));
+    }
+
+    // Every parent must exist, and no section may inherit from itself. Each
+    // problem is reported once, by the section declaring the faulty parent,
+    // or by the section at which a cycle starts.
+    fn check_parents(&self) -> Vec<Error> {
+        let find = |name: &str| self.sections.iter().position(|s| s.name() == name);
+        let mut errors = Vec::new();
+        for (index, location) in self.parents.iter() {
+            let section = &self.sections[*index];
+            let mut chain = vec![section.name().to_string()];
+            let mut current = section;
+            while let Some(parent) = current.parent() {
+                if chain.iter().any(|name| name == parent) {
+                    if chain[0] == parent {
+                        chain.push(parent.to_string());
+                        errors.push(Error::InheritanceCycle {
+                            chain,
+                            location: location.clone(),
+                        });
+                    }
+                    break;
+                }
+                match find(parent) {
+                    Some(found) => {
+                        chain.push(parent.to_string());
+                        current = &self.sections[found];
+                    }
+                    None => {
+                        if chain.len() == 1 {
+                            errors.push(Error::MissingParent {
+                                section: section.name().to_string(),
+                                parent: parent.to_string(),
+                                location: location.clone(),
+                            });
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        errors
+    }
+
+    // Unless empty sections are allowed, each section must hold at least one key
+    fn check_not_empty(&self, current: Option<Current>) -> Result<(), Error> {
+        match current {
+            Some(current) if !current.has_keys && !self.opts.empty_sections => {
+                Err(Error::EmptySection {
+                    name: self.sections[current.index].name().to_string(),
+                    location: current.location,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// Parse a line holding a key value pair. Unlike key_value_pair, the whole
+// line must be consumed, and failures report which part of the line is bad.
+// Returns the key, the text of the line the value was read from, including
+// any quotes, and the value itself.
+pub(crate) fn key_value_line<'a>(
+    line_no: usize,
+    line: &'a str,
+    opts: &ParserOptions,
+) -> Result<(&'a str, &'a str, Cow<'a, str>), Error> {
+    let at = |rest: &str| Location::new(line_no, line, line.len() - rest.len());
+
+    let (rest, _) = space0::<_, (&str, ErrorKind)>(line).unwrap_or((line, ""));
+    let (rest, key) = key_name(rest, opts)
+        .ok_or_else(|| Error::InvalidKey(at(rest).with_length(token_len(rest))))?;
+    let after_key = rest;
+    let (rest, _) = space0::<_, (&str, ErrorKind)>(rest).unwrap_or((rest, ""));
+    let rest = match opts.strip_delimiter(rest) {
+        Some(rest) => rest,
+        None if opts.allow_no_value && (rest.is_empty() || opts.is_inline_comment(rest)) => {
+            return Ok((key, &after_key[..0], Cow::Borrowed("")))
+        }
+        // a delimiter further along the line means the key itself is malformed
+        None if opts.contains_delimiter(rest) => {
+            return Err(Error::InvalidKey(at(rest).with_length(token_len(rest))))
+        }
+        None => return Err(Error::MissingDelimiter(at(rest))),
+    };
+    let (rest, _) = space0::<_, (&str, ErrorKind)>(rest).unwrap_or((rest, ""));
+    let (after_value, value) = match quoted_value(rest) {
+        Ok(result) => result,
+        Err(nom::Err::Failure((bad, ErrorKind::Escaped))) => {
+            return Err(Error::InvalidEscape(at(bad).with_length(2)))
+        }
+        Err(nom::Err::Failure(_)) => {
+            return Err(Error::UnterminatedQuote(
+                at(rest).with_length(rest.trim_end().chars().count()),
+            ))
+        }
+        Err(_) if opts.spaces_in_values => {
+            let end = opts.find_inline_comment(rest).unwrap_or(rest.len());
+            (&rest[end..], Cow::Borrowed(rest[..end].trim_end()))
+        }
+        Err(_) => {
+            let (after_value, value) =
+                until_illegal_char::<_, (&str, ErrorKind)>(rest).unwrap_or((rest, ""));
+            (after_value, Cow::Borrowed(value))
+        }
+    };
+    let is_comment =
+        after_value.starts_with(char::is_whitespace) && opts.is_inline_comment(after_value);
+    if !after_value.trim().is_empty() && !is_comment {
+        return Err(Error::IllegalCharacter {
+            character: after_value.chars().next().unwrap_or(' '),
+            location: at(after_value),
+        });
+    }
+    let raw = rest[..rest.len() - after_value.len()].trim_end();
+    Ok((key, raw, value))
+}
+
+// Parse a header line, returning the name of the section, the name of its
+// parent if one is declared, and whether the header is a tombstone
+pub(crate) fn parse_header<'a>(
+    line: &'a str,
+    opts: &ParserOptions,
+) -> IResult<&'a str, (&'a str, Option<&'a str>, bool)> {
+    if opts.tombstones {
+        if let Ok((rest, name)) = tombstone_header(opts.names)(line) {
+            return Ok((rest, (name, None, true)));
+        }
+    }
+    if opts.inheritance {
+        if let Ok((rest, (name, parent))) = inherit_header(opts.names)(line) {
+            return Ok((rest, (name, Some(parent), false)));
+        }
+    }
+    header(opts.names)(line).map(|(rest, name)| (rest, (name, None, false)))
+}
+
+// Parse a line removing a key, such as `!key`, returning the key
+fn tombstone_line<'a>(
+    line_no: usize,
+    line: &'a str,
+    opts: &ParserOptions,
+) -> Result<&'a str, Error> {
+    let at = |rest: &str| Location::new(line_no, line, line.len() - rest.len());
+    let rest = line.trim_start().trim_start_matches('!');
+    let (rest, _) = space0::<_, (&str, ErrorKind)>(rest).unwrap_or((rest, ""));
+    let (rest, key) = key_name(rest, opts)
+        .ok_or_else(|| Error::InvalidKey(at(rest).with_length(token_len(rest))))?;
+    let is_comment = rest.starts_with(char::is_whitespace) && opts.is_inline_comment(rest);
+    if !rest.trim().is_empty() && !is_comment {
+        return Err(Error::InvalidKey(
+            at(line.trim_start()).with_length(trimmed_len(line)),
+        ));
+    }
+    Ok(key)
+}
+
+// Parse the key at the start of the input, returning the remaining input
+// along with the key. A permissive key runs up to the first delimiter, or
+// inline comment, less any trailing whitespace.
+fn key_name<'a>(input: &'a str, opts: &ParserOptions) -> Option<(&'a str, &'a str)> {
+    match opts.names {
+        Names::Strict => alphaword_many0_underscore_word(input).ok(),
+        Names::Permissive => {
+            let end = [opts.find_delimiter(input), opts.find_inline_comment(input)]
+                .iter()
+                .flatten()
+                .min()
+                .copied()
+                .unwrap_or(input.len());
+            let key = input[..end].trim_end();
+            if key.is_empty() {
+                None
+            } else {
+                Some((&input[key.len()..], key))
+            }
+        }
+    }
+}
+
+// fold the key to lower case if requested, only allocating when necessary
+pub(crate) fn fold_key(key: &str, fold: bool) -> Cow<'_, str> {
+    if fold && key.chars().any(char::is_uppercase) {
+        Cow::Owned(key.to_lowercase())
+    } else {
+        Cow::Borrowed(key)
+    }
+}
+
+// byte offset of the first non whitespace character in the line
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+// remove a trailing backslash, and any whitespace preceding it, from the value,
+// reporting whether there was one
+fn strip_trailing_backslash(value: &mut Cow<'_, str>) -> bool {
+    let stripped = match value.strip_suffix('\\') {
+        Some(stripped) => stripped.trim_end().len(),
+        None => return false,
+    };
+    match value {
+        Cow::Borrowed(text) => *text = &text[..stripped],
+        Cow::Owned(text) => text.truncate(stripped),
+    }
+    true
+}
+
+// length, in chars, of the token at the start of the input
+fn token_len(input: &str) -> usize {
+    input
+        .chars()
+        .take_while(|c| !c.is_whitespace() && *c != '=' && *c != ']')
+        .count()
+}
+
+// length, in chars, of the line minus surrounding whitespace
+fn trimmed_len(line: &str) -> usize {
+    line.trim().chars().count()
+}
+
+// byte offset into the line at which a nom parser gave up
+fn error_offset(line: &str, err: nom::Err<(&str, ErrorKind)>) -> usize {
+    match err {
+        nom::Err::Error((rest, _)) | nom::Err::Failure((rest, _)) => line.len() - rest.len(),
+        nom::Err::Incomplete(_) => line.len(),
+    }
+}
+
+#[cfg(test)]
+#[path = "../unit_tests/parser_driver.rs"]
+mod unit_tests;
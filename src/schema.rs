@@ -0,0 +1,510 @@
+//! schema
+//!
+//! This module houses Schema, which checks that the sections of a config
+//! define the keys expected of them, and that each value is of the expected
+//! type and within the expected bounds. A Schema is assembled from Rules,
+//! either in Rust or by reading a schema cfg, in which each section holding
+//! a `key` describes a rule:
+//!
+//! ```notrust
+//! # settings shared by the rules below
+//! [os]
+//! sections = "cent*"
+//! required = yes
+//!
+//! [os_bits : os]
+//! key = bits
+//! type = int
+//! allowed = "32, 64"
+//!
+//! [os_status : os]
+//! key = status
+//! allowed = "Current, Legacy, Deprecated"
+//! default = Current
+//! ```
+//!
+//! The properties of a rule are read through Config::get, so that shared
+//! settings may be inherited from a parent section. They are:
+//!
+//! - `key`, the key the rule applies to
+//! - `sections`, a glob selecting the sections the rule applies to, such as
+//!   `cent*`. Defaults to every section.
+//! - `type`, one of string, int, float or bool. Defaults to string.
+//! - `required`, a bool. Defaults to no.
+//! - `allowed`, a comma separated list of the values permitted
+//! - `pattern`, a regular expression the value must match. Backslashes
+//!   within a quoted pattern are escapes, and must be doubled, as in `"\\d+"`.
+//! - `min` and `max`, bounding the value as a number
+//! - `default`, which satisfies a required key when it is missing, and is
+//!   filled in by Schema::apply_defaults
+use crate::checks::{at_least, at_most, one_of};
+use crate::error::Error;
+use crate::typed::{convert, split_list, to_bool};
+use crate::{Config, ConfigOwned, Origin, ParserOptions, SectionView};
+use glob::Pattern;
+use regex::Regex;
+use std::fmt;
+use std::path::Path;
+
+// the properties of a rule within a schema cfg
+const PROPERTIES: [&str; 9] = [
+    "key", "sections", "type", "required", "allowed", "pattern", "min", "max", "default",
+];
+
+/// The type the value of a key is expected to hold
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ValueType {
+    /// Any value
+    String,
+    /// A value which parses as an i64
+    Int,
+    /// A value which parses as an f64
+    Float,
+    /// A value accepted by Section::get_bool, such as yes or no
+    Bool,
+}
+
+/// The expectations placed upon a single key.
+///
+/// # Example
+///
+/// ```
+/// use cfgparser::{Rule, ValueType};
+///
+/// let bits = Rule::new("bits")
+///     .value_type(ValueType::Int)
+///     .required(true)
+///     .allowed(vec!["32", "64"]);
+/// assert_eq!(bits.key(), "bits");
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Rule {
+    key: String,
+    value_type: ValueType,
+    required: bool,
+    allowed: Vec<String>,
+    pattern: Option<String>,
+    min: Option<f64>,
+    max: Option<f64>,
+    default: Option<String>,
+}
+
+impl Rule {
+    /// Create a new Rule for the supplied key, which accepts any value and
+    /// permits the key to be missing
+    pub fn new<K>(key: K) -> Self
+    where
+        K: Into<String>,
+    {
+        Self {
+            key: key.into(),
+            value_type: ValueType::String,
+            required: false,
+            allowed: Vec::new(),
+            pattern: None,
+            min: None,
+            max: None,
+            default: None,
+        }
+    }
+
+    /// Retrieve the key the rule applies to
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Set the type the value must hold. Defaults to ValueType::String.
+    pub fn value_type(mut self, value_type: ValueType) -> Self {
+        self.value_type = value_type;
+        self
+    }
+
+    /// When true, the key must be defined, unless the rule supplies a
+    /// default. Defaults to false.
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Set the values permitted. Defaults to permitting any value.
+    pub fn allowed<I, S>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed = values.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set a regular expression the value must match. The expression may
+    /// match any part of the value, unless anchored with `^` and `$`.
+    pub fn pattern<S>(mut self, pattern: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    /// Set the smallest number the value may hold
+    pub fn min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Set the largest number the value may hold
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Set the value assumed when the key is missing
+    pub fn default_value<S>(mut self, value: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.default = Some(value.into());
+        self
+    }
+}
+
+/// A problem found by Schema::validate, along with where the offending value
+/// was defined. The origin is None for a missing key, or a malformed rule.
+#[derive(Debug)]
+pub struct Violation {
+    /// The problem, which is one of Error::MissingKey, Error::InvalidValue,
+    /// Error::Constraint or Error::InvalidSchema
+    pub error: Error,
+    /// Where the offending value was defined
+    pub origin: Option<Origin>,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.origin {
+            Some(origin) => write!(f, "{}: {}", origin, self.error),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+// a rule, along with the sections it applies to and its compiled pattern
+#[derive(Debug, Clone)]
+struct Entry {
+    sections: Pattern,
+    rule: Rule,
+    regex: Option<Regex>,
+}
+
+/// A set of Rules, each applying to the sections whose names match a glob.
+///
+/// # Example
+///
+/// ```
+/// use cfgparser::{Config, Rule, Schema, ValueType};
+///
+/// let schema = Schema::new()
+///     .rule("cent*", Rule::new("bits").value_type(ValueType::Int).min(32.0).max(64.0))
+///     .rule("cent*", Rule::new("status").required(true));
+///
+/// let config = Config::parse_cfg_from_str("[cent7_64]\nbits = 128\n").unwrap().to_owned();
+/// let violations = schema.validate(&config);
+/// assert_eq!(violations.len(), 2);
+/// assert_eq!(
+///     violations[0].to_string(),
+///     "line 2: value '128' of key 'bits' in section 'cent7_64' must be at most 64"
+/// );
+/// assert_eq!(
+///     violations[1].to_string(),
+///     "missing key 'status' in section 'cent7_64'"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    entries: Vec<Entry>,
+    // why each malformed rule added via Schema::rule was left out
+    malformed: Vec<String>,
+}
+
+impl Schema {
+    /// Create a new, empty Schema
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule applying to the sections whose names match the supplied
+    /// glob, such as `cent*` or `*`. If the glob, or the pattern of the rule,
+    /// is malformed, the rule is left out, and validate reports it as
+    /// Error::InvalidSchema.
+    pub fn rule(mut self, sections: &str, rule: Rule) -> Self {
+        match entry(sections, rule) {
+            Ok(entry) => self.entries.push(entry),
+            Err(message) => self.malformed.push(message),
+        }
+        self
+    }
+
+    /// Read a schema from the text of a schema cfg, parsed with section
+    /// inheritance enabled
+    pub fn parse_cfg_from_str(input: &str) -> Result<Self, Error> {
+        let opts = ParserOptions::new().inheritance(true);
+        Self::from_config(&Config::parse_with(input, &opts)?.to_owned())
+    }
+
+    /// Read a schema from the schema cfg at the supplied path, parsed with
+    /// section inheritance enabled
+    pub fn from_path<P>(cfg_path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let opts = ParserOptions::new().inheritance(true);
+        Self::from_config(&crate::from_path_with(cfg_path, &opts)?)
+    }
+
+    /// Read a schema from a schema cfg which has already been parsed. Each
+    /// section holding a `key` describes a rule; other sections are ignored,
+    /// but may hold settings for rules to inherit. See
+    /// ParserOptions::inheritance.
+    pub fn from_config(config: &ConfigOwned) -> Result<Self, Error> {
+        let mut names = config.iter().map(|(name, _)| name).collect::<Vec<_>>();
+        names.sort();
+        let mut schema = Self::new();
+        for name in names {
+            let view = match config.get(name) {
+                Some(view) => view,
+                None => continue,
+            };
+            if let Some(entry) = read_entry(name, &view)? {
+                schema.entries.push(entry);
+            }
+        }
+        Ok(schema)
+    }
+
+    /// Check every section of the config against the rules which apply to
+    /// it, returning every violation found. Malformed rules are reported
+    /// first, then sections are checked in order of their names, and the
+    /// violations within a section are ordered by line. Values inherited from
+    /// a parent section, or the defaults section, are checked as part of each
+    /// section inheriting them.
+    pub fn validate(&self, config: &ConfigOwned) -> Vec<Violation> {
+        let mut violations = self
+            .malformed
+            .iter()
+            .map(|message| Violation {
+                error: Error::InvalidSchema {
+                    section: None,
+                    message: message.clone(),
+                },
+                origin: None,
+            })
+            .collect::<Vec<_>>();
+        for name in section_names(config) {
+            let view = match config.get(name) {
+                Some(view) => view,
+                None => continue,
+            };
+            let start = violations.len();
+            for entry in self.entries.iter() {
+                if entry.sections.matches(name) {
+                    entry.check(config, &view, &mut violations);
+                }
+            }
+            // report the section's violations in the order of its lines,
+            // followed by its missing keys
+            violations[start..].sort_by_key(|violation| {
+                violation
+                    .origin
+                    .as_ref()
+                    .map_or(usize::MAX, |origin| origin.line)
+            });
+        }
+        violations
+    }
+
+    /// Set each key which is missing, but for which a rule applying to its
+    /// section supplies a default
+    pub fn apply_defaults(&self, config: &mut ConfigOwned) {
+        let mut missing = Vec::new();
+        for name in section_names(config) {
+            let view = match config.get(name) {
+                Some(view) => view,
+                None => continue,
+            };
+            for entry in self.entries.iter() {
+                let rule = &entry.rule;
+                if let Some(default) = &rule.default {
+                    if entry.sections.matches(name) && !view.contains_key(&rule.key) {
+                        missing.push((name.to_string(), rule.key.clone(), default.clone()));
+                    }
+                }
+            }
+        }
+        for (section, key, value) in missing {
+            config.set(section, key, value);
+        }
+    }
+}
+
+impl Entry {
+    // record each way in which the section breaks the rule
+    fn check(&self, config: &ConfigOwned, view: &SectionView<'_>, out: &mut Vec<Violation>) {
+        let rule = &self.rule;
+        let section = Some(view.name());
+        let value = match view.get(&rule.key) {
+            Some(value) => value,
+            None => {
+                if rule.required && rule.default.is_none() {
+                    out.push(Violation {
+                        error: Error::MissingKey {
+                            section: section.map(str::to_string),
+                            key: rule.key.clone(),
+                        },
+                        origin: None,
+                    });
+                }
+                return;
+            }
+        };
+        let origin = config.origin(view.name(), &rule.key);
+        let mut push = |error| {
+            out.push(Violation {
+                error,
+                origin: origin.cloned(),
+            })
+        };
+        let constraint = |message| Error::Constraint {
+            section: section.map(str::to_string),
+            key: rule.key.clone(),
+            value: value.to_string(),
+            message,
+        };
+
+        let converted = match rule.value_type {
+            ValueType::String => Ok(None),
+            ValueType::Int => convert::<i64>(section, &rule.key, value).map(|n| Some(n as f64)),
+            ValueType::Float => convert::<f64>(section, &rule.key, value).map(Some),
+            ValueType::Bool => to_bool(section, &rule.key, value).map(|_| None),
+        };
+        let number = match converted {
+            Ok(number) => number,
+            Err(error) => return push(error),
+        };
+        if !rule.allowed.is_empty() {
+            let allowed = rule.allowed.iter().map(String::as_str).collect::<Vec<_>>();
+            if let Err(message) = one_of(value, &allowed) {
+                push(constraint(message));
+            }
+        }
+        if let Some(regex) = &self.regex {
+            if !regex.is_match(value) {
+                push(constraint(format!("must match the pattern '{}'", regex)));
+            }
+        }
+        if rule.min.is_none() && rule.max.is_none() {
+            return;
+        }
+        let number = match number {
+            Some(number) => number,
+            None => match convert::<f64>(section, &rule.key, value) {
+                Ok(number) => number,
+                Err(error) => return push(error),
+            },
+        };
+        let bounds = rule
+            .min
+            .map(|min| at_least(&number, min))
+            .into_iter()
+            .chain(rule.max.map(|max| at_most(&number, max)));
+        for result in bounds {
+            if let Err(message) = result {
+                push(constraint(message));
+            }
+        }
+    }
+}
+
+// the names of the sections to check, in sorted order, skipping the
+// defaults section, whose keys are checked as part of the other sections
+fn section_names(config: &ConfigOwned) -> Vec<&str> {
+    let defaults = config.defaults();
+    let mut names = config
+        .iter()
+        .filter(|(_, section)| !defaults.is_some_and(|defaults| std::ptr::eq(defaults, *section)))
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    names
+}
+
+// compile the glob and pattern of a rule, describing why either is malformed
+fn entry(sections: &str, rule: Rule) -> Result<Entry, String> {
+    let sections =
+        Pattern::new(sections).map_err(|e| format!("malformed glob '{}': {}", sections, e.msg))?;
+    let regex = match &rule.pattern {
+        Some(pattern) => Some(
+            Regex::new(pattern).map_err(|e| format!("malformed pattern '{}': {}", pattern, e))?,
+        ),
+        None => None,
+    };
+    Ok(Entry {
+        sections,
+        rule,
+        regex,
+    })
+}
+
+// read the rule described by a section of a schema cfg, if it describes one
+fn read_entry(name: &str, view: &SectionView<'_>) -> Result<Option<Entry>, Error> {
+    let invalid = |message: String| Error::InvalidSchema {
+        section: Some(name.to_string()),
+        message,
+    };
+    let key = match view.get("key") {
+        Some(key) => key,
+        None => return Ok(None),
+    };
+    if let Some(item) = view.iter().find(|item| !PROPERTIES.contains(&item.key)) {
+        return Err(invalid(format!("unknown property '{}'", item.key)));
+    }
+    let section = Some(name);
+    let mut rule = Rule::new(key);
+    if let Some(value_type) = view.get("type") {
+        rule.value_type = match value_type {
+            "string" => ValueType::String,
+            "int" => ValueType::Int,
+            "float" => ValueType::Float,
+            "bool" => ValueType::Bool,
+            other => {
+                return Err(invalid(format!(
+                    "unknown type '{}', expected one of string, int, float or bool",
+                    other
+                )))
+            }
+        };
+    }
+    if let Some(required) = view.get("required") {
+        rule.required = to_bool(section, "required", required)?;
+    }
+    if let Some(allowed) = view.get("allowed") {
+        rule = rule.allowed(split_list(allowed, ","));
+    }
+    if let Some(pattern) = view.get("pattern") {
+        rule = rule.pattern(pattern);
+    }
+    if let Some(min) = view.get("min") {
+        rule.min = Some(convert(section, "min", min)?);
+    }
+    if let Some(max) = view.get("max") {
+        rule.max = Some(convert(section, "max", max)?);
+    }
+    if let Some(default) = view.get("default") {
+        rule.default = Some(default.to_string());
+    }
+    let sections = view.get("sections").unwrap_or("*");
+    entry(sections, rule).map(Some).map_err(invalid)
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/schema.rs"]
+mod unit_tests;
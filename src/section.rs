@@ -1,29 +1,277 @@
+use crate::typed::{convert, split_list, to_bool};
+use crate::view::Items;
+use crate::Error;
+use crate::MergeStrategy;
+use std::borrow::{Borrow, Cow};
+use std::fmt;
+use std::hash::Hash;
+use std::ops::Index;
+use std::path::PathBuf;
+use std::str::FromStr;
+
 #[cfg(feature = "ordered")]
 use indexmap::IndexMap as HashMap;
 
 #[cfg(not(feature = "ordered"))]
 use std::collections::HashMap;
 
-#[derive(Debug, PartialEq, Clone)]
+#[cfg(not(feature = "ordered"))]
+use std::collections::hash_map::{IntoIter, ValuesMut};
+
+#[cfg(feature = "ordered")]
+use indexmap::map::{IntoIter, ValuesMut};
+
+/// Where a value was defined
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Origin {
+    /// Path to the cfg, if it was read from disk
+    pub path: Option<PathBuf>,
+    /// Line number of the key, starting at 1
+    pub line: usize,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}:{}", path.display(), self.line),
+            None => write!(f, "line {}", self.line),
+        }
+    }
+}
+
+/// Sections compare equal when their names and items are equal, regardless
+/// of where the items were defined.
+#[derive(Debug, Clone)]
 pub struct Section<'a> {
-    name: &'a str,
-    items: HashMap<&'a str, &'a str>,
+    name: Cow<'a, str>,
+    items: HashMap<Cow<'a, str>, Cow<'a, str>>,
+    origins: HashMap<Cow<'a, str>, Origin>,
+    removed: Vec<Cow<'a, str>>,
+    replaces: bool,
+    parent: Option<Cow<'a, str>>,
+}
+
+impl PartialEq for Section<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.items == other.items
+            && self.removed == other.removed
+            && self.replaces == other.replaces
+            && self.parent == other.parent
+    }
 }
 
 impl<'a> Section<'a> {
     /// Create a new Section with the supplied name
-    pub fn new(name: &'a str) -> Self {
+    pub fn new<N>(name: N) -> Self
+    where
+        N: Into<Cow<'a, str>>,
+    {
         Self {
-            name,
+            name: name.into(),
             items: HashMap::new(),
+            origins: HashMap::new(),
+            removed: Vec::new(),
+            replaces: false,
+            parent: None,
+        }
+    }
+
+    // Create a new Section, declared with a tombstone header such as `[!name]`
+    pub(crate) fn replacing<N>(name: N) -> Self
+    where
+        N: Into<Cow<'a, str>>,
+    {
+        Self {
+            replaces: true,
+            ..Self::new(name)
         }
     }
-    pub fn name(&self) -> &'a str {
-        self.name
+    /// Retrieve the name of the section
+    pub fn name(&self) -> &str {
+        &self.name
     }
-    /// Insert a key and value into the items map
-    pub fn insert(&mut self, key: &'a str, value: &'a str) -> Option<&'a str> {
-        self.items.insert(key, value)
+
+    // the name of the section, borrowed from the cfg where possible
+    pub(crate) fn cow_name(&self) -> Cow<'a, str> {
+        self.name.clone()
+    }
+    /// Insert a key and value into the items map. Keys and values are either
+    /// borrowed from the cfg, or owned, when they had to be case folded or
+    /// unescaped.
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Option<Cow<'a, str>>
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        let key = key.into();
+        self.origins.remove(&key);
+        self.items.insert(key, value.into())
+    }
+
+    // insert a key and value parsed from the supplied line of the cfg
+    pub(crate) fn insert_parsed(&mut self, key: Cow<'a, str>, value: Cow<'a, str>, line: usize) {
+        self.removed.retain(|removed| removed != &key);
+        self.origins
+            .insert(key.clone(), Origin { path: None, line });
+        self.items.insert(key, value);
+    }
+
+    // remove a key named by a tombstone, such as `!key`, remembering that it
+    // was removed so that it may also be removed from earlier layers
+    pub(crate) fn remove_parsed(&mut self, key: Cow<'a, str>) {
+        remove_ordered(&mut self.items, &key);
+        remove_ordered(&mut self.origins, &key);
+        if !self.removed.contains(&key) {
+            self.removed.push(key);
+        }
+    }
+
+    /// Retrieve the keys removed via tombstones such as `!key`. When layering
+    /// configs, these keys are removed from the section in earlier layers.
+    /// See ParserOptions::tombstones.
+    pub fn removed_keys(&self) -> &[Cow<'a, str>] {
+        &self.removed
+    }
+
+    /// Retrieve the name of the section this section inherits from, if any.
+    /// See ParserOptions::inheritance.
+    pub fn parent(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+
+    /// Set the name of the section this section inherits from
+    pub fn set_parent<P>(&mut self, parent: P)
+    where
+        P: Into<Cow<'a, str>>,
+    {
+        self.parent = Some(parent.into());
+    }
+
+    /// Was the section declared with a tombstone header such as `[!name]`.
+    /// When layering configs, such a section replaces the section of the same
+    /// name in earlier layers, or removes it if the section has no keys.
+    pub fn replaces(&self) -> bool {
+        self.replaces
+    }
+
+    /// Retrieve where the value of a key was defined, if it was parsed from a
+    /// cfg rather than inserted by hand.
+    pub fn origin(&self, key: &str) -> Option<&Origin> {
+        self.origins.get(key)
+    }
+
+    // mutable access to the origins, so that their paths may be filled in
+    pub(crate) fn origins_mut(&mut self) -> ValuesMut<'_, Cow<'a, str>, Origin> {
+        self.origins.values_mut()
+    }
+
+    /// Retrieve the value of a key. Only the keys the section defines itself
+    /// are consulted; see Config::get to include inherited keys.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::Config;
+    ///
+    /// let config = Config::parse_cfg_from_str("[os]\nbits = 64\narch = x86_64\n").unwrap();
+    /// let section = config.section("os").unwrap();
+    /// assert_eq!(section.get("bits"), Some("64"));
+    /// assert_eq!(&section["arch"], "x86_64");
+    /// assert_eq!(section.len(), 2);
+    /// for (key, value) in section {
+    ///     println!("{} = {}", key, value);
+    /// }
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.items.get(key).map(|value| value.as_ref())
+    }
+
+    /// Does the section define the supplied key
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.items.contains_key(key)
+    }
+
+    /// Iterate over the keys of the section
+    pub fn keys(&self) -> impl Iterator<Item = &str> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Iterate over the values of the section
+    pub fn values(&self) -> impl Iterator<Item = &str> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Iterate over the keys and values of the section
+    pub fn iter(&self) -> SectionIter<'_> {
+        SectionIter {
+            inner: self.items(),
+        }
+    }
+
+    /// The number of keys in the section
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Does the section hold no keys
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Retrieve the value of a key, converted to any type implementing
+    /// FromStr. Returns Ok(None) if the key is not found, and
+    /// Error::InvalidValue, naming the section, key, value and type, if the
+    /// value does not parse.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::Config;
+    /// use std::net::Ipv4Addr;
+    ///
+    /// let contents = "[site]\nbits = 64\nsecure = yes\nhosts = lichost1,lichost2\nproxy = 10.0.0.1\n";
+    /// let config = Config::parse_cfg_from_str(contents).unwrap();
+    /// let site = config.section("site").unwrap();
+    ///
+    /// assert_eq!(site.get_int("bits").unwrap(), Some(64));
+    /// assert_eq!(site.get_bool("secure").unwrap(), Some(true));
+    /// assert_eq!(site.get_list("hosts", ","), Some(vec!["lichost1", "lichost2"]));
+    /// assert_eq!(site.get_as::<Ipv4Addr>("proxy").unwrap(), Some(Ipv4Addr::new(10, 0, 0, 1)));
+    /// assert!(site.get_float("hosts").is_err());
+    /// ```
+    pub fn get_as<T>(&self, key: &str) -> Result<Option<T>, Error>
+    where
+        T: FromStr,
+    {
+        self.get(key)
+            .map(|value| convert(Some(self.name()), key, value))
+            .transpose()
+    }
+
+    /// Retrieve the value of a key as a boolean. Accepts yes/no, on/off,
+    /// true/false and 1/0, regardless of case. See get_as.
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>, Error> {
+        self.get(key)
+            .map(|value| to_bool(Some(self.name()), key, value))
+            .transpose()
+    }
+
+    /// Retrieve the value of a key as an integer. See get_as.
+    pub fn get_int(&self, key: &str) -> Result<Option<i64>, Error> {
+        self.get_as(key)
+    }
+
+    /// Retrieve the value of a key as a floating point number. See get_as.
+    pub fn get_float(&self, key: &str) -> Result<Option<f64>, Error> {
+        self.get_as(key)
+    }
+
+    /// Retrieve the value of a key as a list, split on the supplied
+    /// separator, such as ",". Whitespace around each item is trimmed, and
+    /// empty items are skipped.
+    pub fn get_list(&self, key: &str, separator: &str) -> Option<Vec<&str>> {
+        self.get(key).map(|value| split_list(value, separator))
     }
 
     /// Convert a Section into a SectionOwned
@@ -32,21 +280,113 @@ impl<'a> Section<'a> {
         for (key, value) in self.items.iter() {
             owned.insert(key.to_string(), value.to_string());
         }
-        (self.name, owned)
+        for (key, origin) in self.origins.iter() {
+            owned.origins.insert(key.to_string(), origin.clone());
+        }
+        owned.removed = self.removed.iter().map(|key| key.to_string()).collect();
+        owned.replaces = self.replaces;
+        owned.parent = self.parent.as_ref().map(|parent| parent.to_string());
+        owned.name = Some(self.name.to_string());
+        (&self.name, owned)
+    }
+}
+
+impl Items for Section<'_> {
+    fn item(&self, key: &str) -> Option<(&str, &str)> {
+        self.items
+            .get_key_value(key)
+            .map(|(key, value)| (key.as_ref(), value.as_ref()))
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = (&str, &str)> + '_> {
+        Box::new(
+            self.items
+                .iter()
+                .map(|(key, value)| (key.as_ref(), value.as_ref())),
+        )
+    }
+
+    fn parent(&self) -> Option<&str> {
+        self.parent()
+    }
+
+    fn removed(&self) -> Vec<&str> {
+        self.removed.iter().map(|key| key.as_ref()).collect()
+    }
+
+    fn replaces(&self) -> bool {
+        self.replaces
+    }
+}
+
+impl<'s, 'a> IntoIterator for &'s Section<'a> {
+    type Item = (&'s str, &'s str);
+    type IntoIter = SectionIter<'s>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+impl<'a> IntoIterator for Section<'a> {
+    type Item = (Cow<'a, str>, Cow<'a, str>);
+    type IntoIter = IntoIter<Cow<'a, str>, Cow<'a, str>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+/// Panics if the section does not define the key
+impl Index<&str> for Section<'_> {
+    type Output = str;
+
+    fn index(&self, key: &str) -> &str {
+        match self.get(key) {
+            Some(value) => value,
+            None => panic!("section '{}' has no key '{}'", self.name, key),
+        }
+    }
+}
+
+impl<'a, K, V> Extend<(K, V)> for Section<'a>
+where
+    K: Into<Cow<'a, str>>,
+    V: Into<Cow<'a, str>>,
+{
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+/// SectionOwned instances compare equal when their items are equal,
+/// regardless of where the items were defined, or of the name of the section.
+#[derive(Debug, Clone, Default)]
 pub struct SectionOwned {
     items: HashMap<String, String>,
+    origins: HashMap<String, Origin>,
+    removed: Vec<String>,
+    replaces: bool,
+    parent: Option<String>,
+    // the name of the section, once it belongs to a config
+    name: Option<String>,
+}
+
+impl PartialEq for SectionOwned {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+            && self.removed == other.removed
+            && self.replaces == other.replaces
+            && self.parent == other.parent
+    }
 }
 
 impl SectionOwned {
     /// Create a new Section with the supplied name
     pub fn new() -> Self {
-        Self {
-            items: HashMap::new(),
-        }
+        Self::default()
     }
 
     /// Insert a key and value into the items map
@@ -55,6 +395,358 @@ impl SectionOwned {
         K: Into<String>,
         V: Into<String>,
     {
-        self.items.insert(key.into(), value.into())
+        let key = key.into();
+        self.origins.remove(&key);
+        self.items.insert(key, value.into())
+    }
+
+    /// Retrieve the value of a key. See Section::get.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.items.get(key).map(String::as_str)
+    }
+
+    /// Does the section define the supplied key
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.items.contains_key(key)
+    }
+
+    /// Iterate over the keys of the section
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.items.keys().map(String::as_str)
+    }
+
+    /// Iterate over the values of the section
+    pub fn values(&self) -> impl Iterator<Item = &str> {
+        self.items.values().map(String::as_str)
+    }
+
+    /// Iterate over the keys and values of the section
+    pub fn iter(&self) -> SectionIter<'_> {
+        SectionIter {
+            inner: self.items(),
+        }
+    }
+
+    /// The number of keys in the section
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Does the section hold no keys
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Retrieve the value of a key, converted to any type implementing
+    /// FromStr. See Section::get_as. A SectionOwned does not know its name,
+    /// so any Error::InvalidValue returned has no section.
+    pub fn get_as<T>(&self, key: &str) -> Result<Option<T>, Error>
+    where
+        T: FromStr,
+    {
+        self.get(key)
+            .map(|value| convert(self.name(), key, value))
+            .transpose()
+    }
+
+    /// Retrieve the value of a key as a boolean. See Section::get_bool.
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>, Error> {
+        self.get(key)
+            .map(|value| to_bool(self.name(), key, value))
+            .transpose()
+    }
+
+    /// Retrieve the value of a key as an integer. See get_as.
+    pub fn get_int(&self, key: &str) -> Result<Option<i64>, Error> {
+        self.get_as(key)
+    }
+
+    /// Retrieve the value of a key as a floating point number. See get_as.
+    pub fn get_float(&self, key: &str) -> Result<Option<f64>, Error> {
+        self.get_as(key)
+    }
+
+    /// Retrieve the value of a key as a list. See Section::get_list.
+    pub fn get_list(&self, key: &str, separator: &str) -> Option<Vec<&str>> {
+        self.get(key).map(|value| split_list(value, separator))
+    }
+
+    /// Retrieve a mutable reference to the value of a key. As the value may
+    /// be changed, the key's origin is cleared, as it is by insert.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut String> {
+        let value = self.items.get_mut(key)?;
+        self.origins.remove(key);
+        Some(value)
+    }
+
+    /// Retrieve the entry for a key, for in-place insertion or modification.
+    /// Inserting or modifying the value through the entry clears the key's
+    /// origin.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cfgparser::SectionOwned;
+    ///
+    /// let mut section = SectionOwned::new();
+    /// section.entry("hosts".to_string()).or_default().push_str("lichost1");
+    /// section.entry("hosts".to_string()).or_default().push_str(",lichost2");
+    /// assert_eq!(section.get_mut("hosts").unwrap(), "lichost1,lichost2");
+    /// ```
+    pub fn entry(&mut self, key: String) -> SectionEntry<'_> {
+        SectionEntry { section: self, key }
+    }
+
+    /// Remove a key, returning its value if it was present
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        remove_ordered(&mut self.origins, key);
+        remove_ordered(&mut self.items, key)
+    }
+
+    /// Keep only the keys for which the predicate returns true
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&str, &mut String) -> bool,
+    {
+        self.items.retain(|key, value| keep(key, value));
+        let items = &self.items;
+        self.origins.retain(|key, _| items.contains_key(key));
+    }
+
+    /// Remove every key from the section. Tombstones and the parent are kept.
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.origins.clear();
+    }
+
+    /// Retrieve where the value of a key was defined, if it was parsed from a
+    /// cfg rather than inserted by hand.
+    pub fn origin(&self, key: &str) -> Option<&Origin> {
+        self.origins.get(key)
+    }
+
+    /// Retrieve the name of the section. A section only has a name once it
+    /// belongs to a ConfigOwned, or when it was created via Section::to_owned.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    // name the section, as it is added to a config
+    pub(crate) fn set_name<N>(&mut self, name: N)
+    where
+        N: Into<String>,
+    {
+        self.name = Some(name.into());
+    }
+
+    /// Retrieve the keys removed via tombstones such as `!key`. See
+    /// Section::removed_keys.
+    pub fn removed_keys(&self) -> &[String] {
+        &self.removed
+    }
+
+    /// Retrieve the name of the section this section inherits from, if any.
+    /// See ParserOptions::inheritance.
+    pub fn parent(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+
+    /// Set the name of the section this section inherits from
+    pub fn set_parent<P>(&mut self, parent: P)
+    where
+        P: Into<String>,
+    {
+        self.parent = Some(parent.into());
+    }
+
+    /// Was the section declared with a tombstone header such as `[!name]`.
+    /// See Section::replaces.
+    pub fn replaces(&self) -> bool {
+        self.replaces
+    }
+
+    // forget any tombstones, once they have been applied
+    pub(crate) fn clear_tombstones(&mut self) {
+        self.removed.clear();
+        self.replaces = false;
+    }
+
+    // Merge the keys of a later layer into the section, applying its tombstones
+    pub(crate) fn merge(&mut self, mut other: SectionOwned, strategy: MergeStrategy) {
+        if other.parent.is_some() {
+            self.parent = other.parent;
+        }
+        for key in other.removed.iter() {
+            remove_ordered(&mut self.items, key);
+            remove_ordered(&mut self.origins, key);
+        }
+        for (key, value) in other.items.into_iter() {
+            if strategy == MergeStrategy::Preserve && self.items.contains_key(&key) {
+                continue;
+            }
+            match other.origins.remove(&key) {
+                Some(origin) => self.origins.insert(key.clone(), origin),
+                None => self.origins.remove(&key),
+            };
+            self.items.insert(key, value);
+        }
+    }
+}
+
+impl Items for SectionOwned {
+    fn item(&self, key: &str) -> Option<(&str, &str)> {
+        self.items
+            .get_key_value(key)
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = (&str, &str)> + '_> {
+        Box::new(
+            self.items
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        )
+    }
+
+    fn parent(&self) -> Option<&str> {
+        self.parent()
+    }
+
+    fn removed(&self) -> Vec<&str> {
+        self.removed.iter().map(String::as_str).collect()
+    }
+
+    fn replaces(&self) -> bool {
+        self.replaces
+    }
+}
+
+impl<'s> IntoIterator for &'s SectionOwned {
+    type Item = (&'s str, &'s str);
+    type IntoIter = SectionIter<'s>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for SectionOwned {
+    type Item = (String, String);
+    type IntoIter = IntoIter<String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+/// Panics if the section does not define the key
+impl Index<&str> for SectionOwned {
+    type Output = str;
+
+    fn index(&self, key: &str) -> &str {
+        match self.get(key) {
+            Some(value) => value,
+            None => panic!("section has no key '{}'", key),
+        }
+    }
+}
+
+impl<K, V> Extend<(K, V)> for SectionOwned
+where
+    K: Into<String>,
+    V: Into<String>,
+{
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+/// An iterator over the keys and values of a Section or SectionOwned
+pub struct SectionIter<'s> {
+    inner: Box<dyn Iterator<Item = (&'s str, &'s str)> + 's>,
+}
+
+impl<'s> Iterator for SectionIter<'s> {
+    type Item = (&'s str, &'s str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A key within a SectionOwned, which may or may not be present. Retrieved
+/// via SectionOwned::entry.
+pub struct SectionEntry<'s> {
+    section: &'s mut SectionOwned,
+    key: String,
+}
+
+impl<'s> SectionEntry<'s> {
+    /// Retrieve the key of the entry
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Modify the value in place, if the key is present
+    pub fn and_modify<F>(self, modify: F) -> Self
+    where
+        F: FnOnce(&mut String),
+    {
+        if let Some(value) = self.section.get_mut(&self.key) {
+            modify(value);
+        }
+        self
+    }
+
+    /// Retrieve the value, inserting the supplied default if the key is not
+    /// present
+    pub fn or_insert<V>(self, default: V) -> &'s mut String
+    where
+        V: Into<String>,
+    {
+        self.or_insert_with(|| default.into())
+    }
+
+    /// Retrieve the value, inserting the result of the supplied function if
+    /// the key is not present
+    pub fn or_insert_with<F>(self, default: F) -> &'s mut String
+    where
+        F: FnOnce() -> String,
+    {
+        self.section.origins.remove(&self.key);
+        self.section.items.entry(self.key).or_insert_with(default)
+    }
+
+    /// Retrieve the value, inserting an empty value if the key is not present
+    pub fn or_default(self) -> &'s mut String {
+        self.or_insert_with(String::new)
     }
 }
+
+// Remove a key from a map. With the `ordered` feature, the remaining keys keep
+// their order.
+#[cfg(not(feature = "ordered"))]
+pub(crate) fn remove_ordered<K, V>(map: &mut HashMap<K, V>, key: &str) -> Option<V>
+where
+    K: Borrow<str> + Hash + Eq,
+{
+    map.remove(key)
+}
+
+#[cfg(feature = "ordered")]
+pub(crate) fn remove_ordered<K, V>(map: &mut HashMap<K, V>, key: &str) -> Option<V>
+where
+    K: Borrow<str> + Hash + Eq,
+{
+    map.shift_remove(key)
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/section.rs"]
+mod unit_tests;
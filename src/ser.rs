@@ -0,0 +1,929 @@
+//! ser
+//!
+//! This module, available with the `serde` feature, serializes Rust types
+//! as cfg text. It is the counterpart of the de module: a struct, or a map,
+//! whose fields are sections, each of which is a struct, or a map, whose
+//! fields are keys:
+//!
+//! ```
+//! use serde::Serialize;
+//! use std::collections::BTreeMap;
+//!
+//! #[derive(Serialize)]
+//! struct OperatingSystem {
+//!     bits: u8,
+//!     hosts: Vec<String>,
+//!     legacy: Option<String>,
+//! }
+//!
+//! let mut systems = BTreeMap::new();
+//! let hosts = vec!["lichost1".to_string(), "lichost2".to_string()];
+//! systems.insert("cent7_64", OperatingSystem { bits: 64, hosts, legacy: None });
+//!
+//! let text = cfgparser::to_string(&systems).unwrap();
+//! assert_eq!(text, "[cent7_64]\nbits = 64\nhosts = lichost1,lichost2\n");
+//! ```
+//!
+//! Sections and keys are written in the order they are serialized. A None
+//! is skipped, unit enum variants are written by name, and a sequence is
+//! joined into a single value with FormatOptions::list_separator. Anything
+//! nested more deeply than sections of keys, such as a struct within a
+//! section, is rejected with an Error naming the section and key at fault.
+use crate::de::locate;
+use crate::error::Error;
+use crate::view::Items;
+use crate::writer::{is_valid_name, write_config};
+use crate::{FormatOptions, SectionOwned};
+use serde::ser::{
+    self, Impossible, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple,
+    SerializeTupleStruct,
+};
+use std::fmt;
+
+impl ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Error::Serde {
+            section: None,
+            key: None,
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// Serialize the value as cfg text, using the default FormatOptions
+pub fn to_string<T>(value: &T) -> Result<String, Error>
+where
+    T: Serialize + ?Sized,
+{
+    to_string_with(value, &FormatOptions::default())
+}
+
+/// Serialize the value as cfg text, using the supplied FormatOptions. Fails
+/// with Error::InvalidName if a section name or key could not be read back.
+pub fn to_string_with<T>(value: &T, opts: &FormatOptions) -> Result<String, Error>
+where
+    T: Serialize + ?Sized,
+{
+    let mut sections = Vec::new();
+    value.serialize(ConfigSerializer {
+        sections: &mut sections,
+        separator: opts.separator(),
+    })?;
+    for (name, keys) in sections.iter() {
+        let keys = keys.0.iter().map(|(key, _)| key.as_str());
+        if let Some(invalid) = Some(name.as_str())
+            .into_iter()
+            .chain(keys)
+            .find(|text| !is_valid_name(text, opts.names))
+        {
+            return Err(Error::InvalidName(invalid.to_string()));
+        }
+    }
+    let sections = sections
+        .iter()
+        .map(|(name, keys)| (Some(name.as_str()), keys as &dyn Items))
+        .collect::<Vec<_>>();
+    let mut out = String::new();
+    write_config(&mut out, &sections, opts).map_err(ser::Error::custom)?;
+    Ok(out)
+}
+
+impl Serialize for SectionOwned {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+// the keys of a section, in the order they were serialized
+#[derive(Default)]
+struct Keys(Vec<(String, String)>);
+
+impl Items for Keys {
+    fn item(&self, key: &str) -> Option<(&str, &str)> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = (&str, &str)> + '_> {
+        Box::new(self.0.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+    }
+
+    fn parent(&self) -> Option<&str> {
+        None
+    }
+
+    fn removed(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    fn replaces(&self) -> bool {
+        false
+    }
+}
+
+// implement Serializer methods which reject what they are handed, describing
+// it with the supplied text
+macro_rules! reject {
+    ($($method:ident($($arg:ident: $ty:ty),*) -> $ret:ty = $what:expr;)*) => {
+        $(
+            fn $method(self, $($arg: $ty),*) -> Result<$ret, Error> {
+                $(let _ = $arg;)*
+                Err(self.reject($what))
+            }
+        )*
+    };
+}
+
+// collects the sections of a config from a struct or map
+struct ConfigSerializer<'s> {
+    sections: &'s mut Vec<(String, Keys)>,
+    separator: &'s str,
+}
+
+impl ConfigSerializer<'_> {
+    fn reject(&self, what: &str) -> Error {
+        ser::Error::custom(format!(
+            "a cfg must be serialized from a struct or map of sections, not {}",
+            what
+        ))
+    }
+}
+
+impl<'s> ser::Serializer for ConfigSerializer<'s> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = ConfigMap<'s>;
+    type SerializeStruct = ConfigMap<'s>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    reject! {
+        serialize_bool(v: bool) -> () = "a bool";
+        serialize_i8(v: i8) -> () = "a number";
+        serialize_i16(v: i16) -> () = "a number";
+        serialize_i32(v: i32) -> () = "a number";
+        serialize_i64(v: i64) -> () = "a number";
+        serialize_u8(v: u8) -> () = "a number";
+        serialize_u16(v: u16) -> () = "a number";
+        serialize_u32(v: u32) -> () = "a number";
+        serialize_u64(v: u64) -> () = "a number";
+        serialize_f32(v: f32) -> () = "a number";
+        serialize_f64(v: f64) -> () = "a number";
+        serialize_char(v: char) -> () = "a char";
+        serialize_str(v: &str) -> () = "a string";
+        serialize_bytes(v: &[u8]) -> () = "bytes";
+        serialize_unit_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str
+        ) -> () = "an enum";
+        serialize_seq(len: Option<usize>) -> Self::SerializeSeq = "a sequence";
+        serialize_tuple(len: usize) -> Self::SerializeTuple = "a tuple";
+        serialize_tuple_struct(
+            name: &'static str,
+            len: usize
+        ) -> Self::SerializeTupleStruct = "a tuple";
+        serialize_tuple_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeTupleVariant = "an enum";
+        serialize_struct_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeStructVariant = "an enum";
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(self.reject("an enum"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<ConfigMap<'s>, Error> {
+        Ok(ConfigMap {
+            sections: self.sections,
+            separator: self.separator,
+            name: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<ConfigMap<'s>, Error> {
+        self.serialize_map(Some(len))
+    }
+}
+
+struct ConfigMap<'s> {
+    sections: &'s mut Vec<(String, Keys)>,
+    separator: &'s str,
+    // the name of the section whose value is expected next
+    name: Option<String>,
+}
+
+impl ConfigMap<'_> {
+    fn section<T>(&mut self, name: String, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let serializer = SectionSerializer {
+            name: &name,
+            separator: self.separator,
+        };
+        let keys = value
+            .serialize(serializer)
+            .map_err(|e| locate(e, &name, None))?;
+        if let Some(keys) = keys {
+            self.sections.push((name, keys));
+        }
+        Ok(())
+    }
+}
+
+impl SerializeMap for ConfigMap<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.name = Some(key.serialize(NameSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let name = self
+            .name
+            .take()
+            .ok_or_else(|| ser::Error::custom("section serialized before its name"))?;
+        self.section(name, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl SerializeStruct for ConfigMap<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.section(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+// collects the keys of a section from a struct or map, yielding None when
+// the section is missing
+struct SectionSerializer<'s> {
+    name: &'s str,
+    separator: &'s str,
+}
+
+impl SectionSerializer<'_> {
+    fn reject(&self, what: &str) -> Error {
+        ser::Error::custom(format!(
+            "a section must be serialized from a struct or map of keys, not {}",
+            what
+        ))
+    }
+}
+
+impl<'s> ser::Serializer for SectionSerializer<'s> {
+    type Ok = Option<Keys>;
+    type Error = Error;
+    type SerializeSeq = Impossible<Option<Keys>, Error>;
+    type SerializeTuple = Impossible<Option<Keys>, Error>;
+    type SerializeTupleStruct = Impossible<Option<Keys>, Error>;
+    type SerializeTupleVariant = Impossible<Option<Keys>, Error>;
+    type SerializeMap = SectionMap<'s>;
+    type SerializeStruct = SectionMap<'s>;
+    type SerializeStructVariant = Impossible<Option<Keys>, Error>;
+
+    reject! {
+        serialize_bool(v: bool) -> Option<Keys> = "a bool";
+        serialize_i8(v: i8) -> Option<Keys> = "a number";
+        serialize_i16(v: i16) -> Option<Keys> = "a number";
+        serialize_i32(v: i32) -> Option<Keys> = "a number";
+        serialize_i64(v: i64) -> Option<Keys> = "a number";
+        serialize_u8(v: u8) -> Option<Keys> = "a number";
+        serialize_u16(v: u16) -> Option<Keys> = "a number";
+        serialize_u32(v: u32) -> Option<Keys> = "a number";
+        serialize_u64(v: u64) -> Option<Keys> = "a number";
+        serialize_f32(v: f32) -> Option<Keys> = "a number";
+        serialize_f64(v: f64) -> Option<Keys> = "a number";
+        serialize_char(v: char) -> Option<Keys> = "a char";
+        serialize_str(v: &str) -> Option<Keys> = "a string";
+        serialize_bytes(v: &[u8]) -> Option<Keys> = "bytes";
+        serialize_unit_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str
+        ) -> Option<Keys> = "an enum";
+        serialize_seq(len: Option<usize>) -> Self::SerializeSeq = "a sequence";
+        serialize_tuple(len: usize) -> Self::SerializeTuple = "a tuple";
+        serialize_tuple_struct(
+            name: &'static str,
+            len: usize
+        ) -> Self::SerializeTupleStruct = "a tuple";
+        serialize_tuple_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeTupleVariant = "an enum";
+        serialize_struct_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeStructVariant = "an enum";
+    }
+
+    fn serialize_none(self) -> Result<Option<Keys>, Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Option<Keys>, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Option<Keys>, Error> {
+        Ok(Some(Keys::default()))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Option<Keys>, Error> {
+        Ok(Some(Keys::default()))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Option<Keys>, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Option<Keys>, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(self.reject("an enum"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SectionMap<'s>, Error> {
+        Ok(SectionMap {
+            name: self.name,
+            separator: self.separator,
+            keys: Keys::default(),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SectionMap<'s>, Error> {
+        self.serialize_map(Some(len))
+    }
+}
+
+struct SectionMap<'s> {
+    name: &'s str,
+    separator: &'s str,
+    keys: Keys,
+    // the key whose value is expected next
+    key: Option<String>,
+}
+
+impl SectionMap<'_> {
+    fn value<T>(&mut self, key: String, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let serializer = ValueSerializer {
+            separator: self.separator,
+            in_list: false,
+        };
+        let value = value
+            .serialize(serializer)
+            .map_err(|e| locate(e, self.name, Some(&key)))?;
+        if let Some(value) = value {
+            self.keys.0.push((key, value));
+        }
+        Ok(())
+    }
+}
+
+impl SerializeMap for SectionMap<'_> {
+    type Ok = Option<Keys>;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.key = Some(key.serialize(NameSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| ser::Error::custom("value serialized before its key"))?;
+        self.value(key, value)
+    }
+
+    fn end(self) -> Result<Option<Keys>, Error> {
+        Ok(Some(self.keys))
+    }
+}
+
+impl SerializeStruct for SectionMap<'_> {
+    type Ok = Option<Keys>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.value(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Option<Keys>, Error> {
+        Ok(Some(self.keys))
+    }
+}
+
+// writes a single value, yielding None when the value is missing
+struct ValueSerializer<'s> {
+    separator: &'s str,
+    // is the value an item within a list
+    in_list: bool,
+}
+
+impl ValueSerializer<'_> {
+    fn reject(&self, what: &str) -> Error {
+        ser::Error::custom(format!(
+            "{} is nested too deeply to be written as a value",
+            what
+        ))
+    }
+}
+
+impl<'s> ser::Serializer for ValueSerializer<'s> {
+    type Ok = Option<String>;
+    type Error = Error;
+    type SerializeSeq = ListSerializer<'s>;
+    type SerializeTuple = ListSerializer<'s>;
+    type SerializeTupleStruct = ListSerializer<'s>;
+    type SerializeTupleVariant = Impossible<Option<String>, Error>;
+    type SerializeMap = Impossible<Option<String>, Error>;
+    type SerializeStruct = Impossible<Option<String>, Error>;
+    type SerializeStructVariant = Impossible<Option<String>, Error>;
+
+    reject! {
+        serialize_bytes(v: &[u8]) -> Option<String> = "bytes";
+        serialize_tuple_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeTupleVariant = "an enum";
+        serialize_map(len: Option<usize>) -> Self::SerializeMap = "a map";
+        serialize_struct(name: &'static str, len: usize) -> Self::SerializeStruct = "a struct";
+        serialize_struct_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeStructVariant = "an enum";
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Option<String>, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_none(self) -> Result<Option<String>, Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Option<String>, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Option<String>, Error> {
+        Ok(Some(String::new()))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Option<String>, Error> {
+        Ok(Some(String::new()))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Option<String>, Error> {
+        Ok(Some(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Option<String>, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Option<String>, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(self.reject("an enum with data"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<ListSerializer<'s>, Error> {
+        if self.in_list {
+            return Err(self.reject("a nested sequence"));
+        }
+        Ok(ListSerializer {
+            separator: self.separator,
+            items: Vec::new(),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ListSerializer<'s>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<ListSerializer<'s>, Error> {
+        self.serialize_seq(Some(len))
+    }
+}
+
+// joins the items of a sequence into a single value
+struct ListSerializer<'s> {
+    separator: &'s str,
+    items: Vec<String>,
+}
+
+impl ListSerializer<'_> {
+    fn push<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let serializer = ValueSerializer {
+            separator: self.separator,
+            in_list: true,
+        };
+        if let Some(item) = value.serialize(serializer)? {
+            // an item holding the separator would be split in two when read back
+            let separator = match self.separator.trim() {
+                "" => self.separator,
+                trimmed => trimmed,
+            };
+            if !separator.is_empty() && item.contains(separator) {
+                return Err(ser::Error::custom(format!(
+                    "list item '{}' contains the separator '{}'",
+                    item, self.separator
+                )));
+            }
+            self.items.push(item);
+        }
+        Ok(())
+    }
+
+    fn join(self) -> Option<String> {
+        Some(self.items.join(self.separator))
+    }
+}
+
+impl SerializeSeq for ListSerializer<'_> {
+    type Ok = Option<String>;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Option<String>, Error> {
+        Ok(self.join())
+    }
+}
+
+impl SerializeTuple for ListSerializer<'_> {
+    type Ok = Option<String>;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Option<String>, Error> {
+        Ok(self.join())
+    }
+}
+
+impl SerializeTupleStruct for ListSerializer<'_> {
+    type Ok = Option<String>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Option<String>, Error> {
+        Ok(self.join())
+    }
+}
+
+// writes the name of a section, or a key, taken from the key of a map
+struct NameSerializer;
+
+impl NameSerializer {
+    fn reject(&self, what: &str) -> Error {
+        ser::Error::custom(format!(
+            "section names and keys must be strings or numbers, not {}",
+            what
+        ))
+    }
+}
+
+impl ser::Serializer for NameSerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    reject! {
+        serialize_bool(v: bool) -> String = "a bool";
+        serialize_f32(v: f32) -> String = "a float";
+        serialize_f64(v: f64) -> String = "a float";
+        serialize_bytes(v: &[u8]) -> String = "bytes";
+        serialize_none() -> String = "an option";
+        serialize_unit() -> String = "a unit";
+        serialize_unit_struct(name: &'static str) -> String = "a unit";
+        serialize_seq(len: Option<usize>) -> Self::SerializeSeq = "a sequence";
+        serialize_tuple(len: usize) -> Self::SerializeTuple = "a tuple";
+        serialize_tuple_struct(
+            name: &'static str,
+            len: usize
+        ) -> Self::SerializeTupleStruct = "a tuple";
+        serialize_tuple_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeTupleVariant = "an enum";
+        serialize_map(len: Option<usize>) -> Self::SerializeMap = "a map";
+        serialize_struct(name: &'static str, len: usize) -> Self::SerializeStruct = "a struct";
+        serialize_struct_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> Self::SerializeStructVariant = "an enum";
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<String, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<String, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(self.reject("an enum"))
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/ser.rs"]
+mod unit_tests;
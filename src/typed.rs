@@ -0,0 +1,147 @@
+//! typed
+//!
+//! Values are stored as strings. This module converts them on request, for
+//! the typed getters offered by Section, SectionOwned, Config and
+//! ConfigOwned, such as get_int, get_bool and get_list, and for the
+//! FromValue trait used by `#[derive(CfgSection)]`.
+use crate::error::Error;
+use std::any::type_name;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+// words accepted as booleans, compared without regard to case
+const TRUE: [&str; 4] = ["yes", "on", "true", "1"];
+const FALSE: [&str; 4] = ["no", "off", "false", "0"];
+
+// Convert the value of a key to the target type
+pub(crate) fn convert<T>(section: Option<&str>, key: &str, value: &str) -> Result<T, Error>
+where
+    T: FromStr,
+{
+    value
+        .parse()
+        .map_err(|_| invalid(section, key, value, &short_type_name::<T>()))
+}
+
+// The name of a type, without the paths of the modules holding it or its
+// type parameters, such as `Vec<String>` rather than
+// `alloc::vec::Vec<alloc::string::String>`
+pub(crate) fn short_type_name<T: ?Sized>() -> String {
+    let full = type_name::<T>();
+    let last_segment = |path: &str| path.rsplit("::").next().unwrap_or_default().to_string();
+    let mut short = String::with_capacity(full.len());
+    let mut start = 0;
+    for (idx, c) in full.char_indices() {
+        if !(c.is_alphanumeric() || c == '_' || c == ':') {
+            short.push_str(&last_segment(&full[start..idx]));
+            short.push(c);
+            start = idx + c.len_utf8();
+        }
+    }
+    short.push_str(&last_segment(&full[start..]));
+    short
+}
+
+// Convert the value of a key to a boolean
+pub(crate) fn to_bool(section: Option<&str>, key: &str, value: &str) -> Result<bool, Error> {
+    if TRUE.iter().any(|word| word.eq_ignore_ascii_case(value)) {
+        Ok(true)
+    } else if FALSE.iter().any(|word| word.eq_ignore_ascii_case(value)) {
+        Ok(false)
+    } else {
+        Err(invalid(section, key, value, "bool"))
+    }
+}
+
+// Split a value into a list, trimming whitespace from each item and skipping
+// empty items
+pub(crate) fn split_list<'v>(value: &'v str, separator: &str) -> Vec<&'v str> {
+    value
+        .split(separator)
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Conversion from the text of a value, used by the code generated by
+/// `#[derive(CfgSection)]` for each field of a struct.
+///
+/// Numbers, chars, Strings and PathBufs are parsed via FromStr, bools accept
+/// the same words as Section::get_bool, and a Vec is read from a comma
+/// separated list. Implement it for your own types to use them as fields:
+///
+/// ```
+/// use cfgparser::{Error, FromValue};
+///
+/// enum Status {
+///     Current,
+///     Legacy,
+/// }
+///
+/// impl FromValue for Status {
+///     fn from_value(section: Option<&str>, key: &str, value: &str) -> Result<Self, Error> {
+///         match value {
+///             "Current" => Ok(Status::Current),
+///             "Legacy" => Ok(Status::Legacy),
+///             _ => Err(Error::InvalidValue {
+///                 section: section.map(str::to_string),
+///                 key: key.to_string(),
+///                 value: value.to_string(),
+///                 target: "Status".to_string(),
+///             }),
+///         }
+///     }
+/// }
+/// ```
+pub trait FromValue: Sized {
+    /// Convert the value of the key. The section is None when it is not known.
+    fn from_value(section: Option<&str>, key: &str, value: &str) -> Result<Self, Error>;
+}
+
+// implement FromValue via FromStr
+macro_rules! from_str_value {
+    ($($ty:ty),*) => {
+        $(
+            impl FromValue for $ty {
+                fn from_value(section: Option<&str>, key: &str, value: &str) -> Result<Self, Error> {
+                    convert(section, key, value)
+                }
+            }
+        )*
+    };
+}
+
+from_str_value!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, char, String, PathBuf
+);
+
+impl FromValue for bool {
+    fn from_value(section: Option<&str>, key: &str, value: &str) -> Result<Self, Error> {
+        to_bool(section, key, value)
+    }
+}
+
+impl<T> FromValue for Vec<T>
+where
+    T: FromValue,
+{
+    fn from_value(section: Option<&str>, key: &str, value: &str) -> Result<Self, Error> {
+        split_list(value, ",")
+            .into_iter()
+            .map(|item| T::from_value(section, key, item))
+            .collect()
+    }
+}
+
+fn invalid(section: Option<&str>, key: &str, value: &str, target: &str) -> Error {
+    Error::InvalidValue {
+        section: section.map(str::to_string),
+        key: key.to_string(),
+        value: value.to_string(),
+        target: target.to_string(),
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/typed.rs"]
+mod unit_tests;
@@ -27,3 +27,289 @@ short_name = fr
 
     assert_eq!(result, config);
 }
+
+#[test]
+fn from_cfg_given_bad_str_returns_error() {
+    let contents = "[test]\nthis = is\nthe way\n";
+    let result = Config::parse_cfg_from_str(contents);
+    match result {
+        Err(Error::MissingDelimiter(location)) => {
+            assert_eq!(location.line, 3);
+            assert_eq!(location.source_line, "the way");
+        }
+        _ => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn to_owned_given_multi_line_value_keeps_newlines() {
+    let contents = "[site]\nhosts = lichost1\n  lichost2\n";
+    let opts = ParserOptions::new().continuation(crate::Continuation::Indented);
+    let config = Config::parse_with(contents, &opts).unwrap().to_owned();
+    let mut expected = SectionOwned::new();
+    expected.insert("hosts", "lichost1\nlichost2");
+    assert_eq!(config.section("site"), Some(&expected));
+}
+
+#[test]
+fn from_cfg_given_empty_str_returns_empty_config() {
+    assert_eq!(Config::parse_cfg_from_str("").unwrap(), Config::new());
+    let result = Config::parse_cfg_from_str("# comment only\n");
+    assert_eq!(result.unwrap(), Config::new());
+}
+
+#[test]
+fn globals_given_global_section_option_survives_to_owned() {
+    let contents = "version = 3\n[os]\nbits = 64\n";
+    let opts = ParserOptions::new().global_section("root");
+    let config = Config::parse_with(contents, &opts).unwrap();
+    let mut expected = SectionOwned::new();
+    expected.insert("version", "3");
+    assert_eq!(config.to_owned().globals(), Some(&expected));
+    assert!(Config::parse_cfg_from_str("[os]\nbits = 64\n")
+        .unwrap()
+        .globals()
+        .is_none());
+}
+
+#[test]
+fn get_given_default_section_tells_inherited_from_local() {
+    let contents = "[DEFAULT]\nbits = 64\nstatus = Current\n\n[cent6_64]\nstatus = Legacy\n";
+    let opts = ParserOptions::new().default_section("DEFAULT");
+    let config = Config::parse_with(contents, &opts).unwrap();
+    let view = config.get("cent6_64").unwrap();
+    assert_eq!(view.get("bits"), Some("64"));
+    let owned = config.to_owned();
+    assert_eq!(owned.get("cent6_64").unwrap().get("bits"), Some("64"));
+    let mut items = view
+        .iter()
+        .map(|item| (item.key, item.value, item.section, item.inherited))
+        .collect::<Vec<_>>();
+    items.sort();
+    assert_eq!(
+        items,
+        vec![
+            ("bits", "64", "DEFAULT", true),
+            ("status", "Legacy", "cent6_64", false),
+        ]
+    );
+    // the section itself only holds its own keys
+    let mut expected = Section::new("cent6_64");
+    expected.insert("status", "Legacy");
+    assert_eq!(config.section("cent6_64"), Some(&expected));
+}
+
+#[test]
+fn get_of_default_section_does_not_inherit_from_itself() {
+    let contents = "[DEFAULT]\nbits = 64\n";
+    let opts = ParserOptions::new().default_section("DEFAULT");
+    let config = Config::parse_with(contents, &opts).unwrap();
+    let view = config.get("DEFAULT").unwrap();
+    assert_eq!(view.iter().count(), 1);
+    assert!(!view.item("bits").unwrap().inherited);
+}
+
+#[test]
+fn get_value_without_default_section_option_does_not_fall_back() {
+    let contents = "[DEFAULT]\nbits = 64\n\n[cent7_64]\ntype = LINUX\n";
+    let config = Config::parse_cfg_from_str(contents).unwrap();
+    assert_eq!(config.get_value("cent7_64", "bits"), None);
+    assert_eq!(config.get_value("DEFAULT", "bits"), Some("64"));
+    assert!(config.defaults().is_none());
+}
+
+#[test]
+fn get_value_given_default_section_survives_to_owned() {
+    let contents = "[DEFAULT]\nbits = 64\n\n[cent7_64]\ntype = LINUX\n";
+    let opts = ParserOptions::new().default_section("DEFAULT");
+    let config = Config::parse_with(contents, &opts).unwrap().to_owned();
+    assert_eq!(config.get_value("cent7_64", "bits"), Some("64"));
+    assert_eq!(config.get_value("cent7_64", "type"), Some("LINUX"));
+    let view = config.get("cent7_64").unwrap();
+    assert!(view.item("bits").unwrap().inherited);
+    assert!(!view.item("type").unwrap().inherited);
+    assert!(config.defaults().is_some());
+}
+
+#[test]
+fn get_given_inheritance_follows_parents_then_defaults() {
+    let contents = "[DEFAULT]\nstatus = Current\nbits = 32\n\n[base]\nbits = 64\ntype = LINUX\n\n[cent6_64 : base]\nversion = 6\n\n[cent7_64 : cent6_64]\nversion = 7\n";
+    let opts = ParserOptions::new()
+        .inheritance(true)
+        .default_section("DEFAULT");
+    let config = Config::parse_with(contents, &opts).unwrap().to_owned();
+    let view = config.get("cent7_64").unwrap();
+    assert_eq!(
+        view.chain().collect::<Vec<_>>(),
+        vec!["cent7_64", "cent6_64", "base", "DEFAULT"]
+    );
+    assert_eq!(view.get("version"), Some("7"));
+    assert_eq!(view.get("type"), Some("LINUX"));
+    assert_eq!(view.get("bits"), Some("64"));
+    assert_eq!(view.get("status"), Some("Current"));
+    assert_eq!(view.iter().count(), 4);
+    assert_eq!(view.iter().filter(|item| !item.inherited).count(), 1);
+    assert_eq!(
+        config.section("cent7_64").unwrap().parent(),
+        Some("cent6_64")
+    );
+}
+
+#[test]
+fn get_given_cycle_stops_following_parents() {
+    let mut config = Config::new();
+    let mut a = Section::new("a");
+    a.insert("x", "1");
+    a.set_parent("b");
+    let mut b = Section::new("b");
+    b.insert("y", "2");
+    b.set_parent("a");
+    config.insert("a", a);
+    config.insert("b", b);
+    let view = config.get("a").unwrap();
+    assert_eq!(view.chain().collect::<Vec<_>>(), vec!["a", "b"]);
+    assert_eq!(view.get("y"), Some("2"));
+}
+
+#[test]
+fn insert_given_owned_section_replaces_existing() {
+    let mut config = ConfigOwned::new();
+    let mut section = SectionOwned::new();
+    section.insert("bits", "64");
+    assert!(config.insert("cent7_64", section.clone()));
+    assert!(!config.insert("cent7_64", SectionOwned::new()));
+    assert_eq!(config.section("cent7_64"), Some(&SectionOwned::new()));
+}
+
+#[test]
+fn set_given_new_section_creates_it() {
+    let mut config = ConfigOwned::new();
+    assert_eq!(config.set("cent7_64", "bits", "64"), None);
+    config
+        .section_mut_or_insert("cent7_64")
+        .insert("arch", "x86_64");
+    assert_eq!(config.get_value("cent7_64", "bits"), Some("64"));
+    assert_eq!(config.get_value("cent7_64", "arch"), Some("x86_64"));
+
+    config.get_mut("cent7_64").unwrap().clear();
+    assert_eq!(config.get_value("cent7_64", "bits"), None);
+    assert!(config.get_mut("cent6_64").is_none());
+    assert_eq!(config.remove("cent6_64", "bits"), None);
+}
+
+#[test]
+fn remove_section_given_parsed_config_drops_it() {
+    let contents = "[cent7_64]\nbits = 64\n\n[cent6_64]\nbits = 32\n";
+    let mut config = Config::parse_cfg_from_str(contents).unwrap().to_owned();
+    assert!(config.remove_section("cent6_64").is_some());
+    assert!(config.remove_section("cent6_64").is_none());
+    assert_eq!(
+        config
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["cent7_64"]
+    );
+}
+
+#[test]
+fn rename_section_given_taken_name_does_nothing() {
+    let mut config = ConfigOwned::new();
+    config.set("a", "x", "1");
+    config.set("b", "y", "2");
+    assert!(!config.rename_section("a", "b"));
+    assert!(!config.rename_section("missing", "c"));
+    assert_eq!(config.get_value("a", "x"), Some("1"));
+    assert!(config.rename_section("a", "c"));
+    assert!(config.get("a").is_none());
+    assert_eq!(config.get_value("c", "x"), Some("1"));
+}
+
+#[test]
+fn rename_section_given_globals_and_defaults_follows_them() {
+    let opts = ParserOptions::new()
+        .global_section("root")
+        .default_section("DEFAULT");
+    let contents = "version = 3\n\n[DEFAULT]\nbits = 64\n\n[os]\narch = x86\n";
+    let mut config = Config::parse_with(contents, &opts).unwrap().to_owned();
+    assert!(config.rename_section("root", "top"));
+    assert!(config.rename_section("DEFAULT", "common"));
+    assert!(std::ptr::eq(
+        config.globals().unwrap(),
+        config.section("top").unwrap()
+    ));
+    assert!(std::ptr::eq(
+        config.defaults().unwrap(),
+        config.section("common").unwrap()
+    ));
+    assert_eq!(config.get_value("os", "bits"), Some("64"));
+}
+
+#[cfg(feature = "ordered")]
+#[test]
+fn rename_section_given_ordered_feature_keeps_place() {
+    let contents = "[a]\nx = 1\n[b]\nx = 2\n[c]\nx = 3\n";
+    let mut config = Config::parse_cfg_from_str(contents).unwrap().to_owned();
+    assert!(config.rename_section("a", "z"));
+    config.remove_section("b");
+    assert_eq!(
+        config
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["z", "c"]
+    );
+}
+
+#[test]
+fn retain_given_predicate_keeps_matching_sections() {
+    let mut config = ConfigOwned::new();
+    config.set("cent7_64", "bits", "64");
+    config.set("cent6_32", "bits", "32");
+    config.retain(|name, _| name.ends_with("_64"));
+    assert!(config.get("cent6_32").is_none());
+    assert!(config.get("cent7_64").is_some());
+    config.clear();
+    assert_eq!(config.iter().count(), 0);
+}
+
+#[test]
+fn get_int_given_default_section_converts_inherited_value() {
+    let contents = "[DEFAULT]\nbits = 64\n\n[cent7_64]\nhosts = lichost1 lichost2\n";
+    let opts = ParserOptions::new()
+        .default_section("DEFAULT")
+        .spaces_in_values(true);
+    let config = Config::parse_with(contents, &opts).unwrap().to_owned();
+    assert_eq!(config.get_int("cent7_64", "bits").unwrap(), Some(64));
+    assert_eq!(config.get_int("cent7_64", "missing").unwrap(), None);
+    assert_eq!(
+        config.get_list("cent7_64", "hosts", " "),
+        Some(vec!["lichost1", "lichost2"])
+    );
+    let error = config.get_float("cent7_64", "hosts").unwrap_err();
+    assert!(matches!(
+        error,
+        Error::InvalidValue { section: Some(section), target, .. }
+            if section == "cent7_64" && target == "f64"
+    ));
+}
+
+#[test]
+fn given_fold_case_lookups_fold_the_key() {
+    let opts = ParserOptions::from(crate::Dialect::Python);
+    let config = Config::parse_with("[s]\nB = 1\n", &opts).unwrap();
+    assert_eq!(config.get_value("s", "B"), Some("1"));
+    assert_eq!(config.get_value("s", "b"), Some("1"));
+    assert_eq!(config.get_int("s", "B").unwrap(), Some(1));
+    assert!(config.get("s").unwrap().contains_key("B"));
+    assert_eq!(config.origin("s", "B").unwrap().line, 2);
+
+    let mut owned = config.to_owned();
+    assert_eq!(owned.get_bool("s", "B").unwrap(), Some(true));
+    assert_eq!(owned.set("s", "C", "2"), None);
+    assert_eq!(owned.get_value("s", "c"), Some("2"));
+    assert_eq!(owned.remove("s", "C"), Some("2".to_string()));
+
+    let config = Config::parse_cfg_from_str("[s]\nB = 1\n").unwrap();
+    assert_eq!(config.get_value("s", "b"), None);
+}
@@ -0,0 +1,163 @@
+use super::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Status {
+    Current,
+    Legacy,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct OperatingSystem {
+    architecture: String,
+    bits: u8,
+    python_version: f32,
+    status: Status,
+    secure: Option<bool>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Systems {
+    cent7_64: OperatingSystem,
+    cent8_64: Option<OperatingSystem>,
+}
+
+const CONTENTS: &str = "[cent7_64]
+architecture = linux_cent7_x86_64
+bits = 64
+python_version = 2.7
+status = Current
+secure = yes
+
+[cent6_64]
+architecture = linux_cent6_x86_64
+bits = 64
+python_version = 2.6
+status = Legacy
+";
+
+#[test]
+fn from_str_given_struct_of_structs_coerces_values() {
+    let systems: Systems = from_str(CONTENTS).unwrap();
+    assert_eq!(
+        systems.cent7_64,
+        OperatingSystem {
+            architecture: "linux_cent7_x86_64".to_string(),
+            bits: 64,
+            python_version: 2.7,
+            status: Status::Current,
+            secure: Some(true),
+        }
+    );
+    assert_eq!(systems.cent8_64, None);
+}
+
+#[test]
+fn from_str_given_map_of_sections_reads_every_section() {
+    let systems: HashMap<String, OperatingSystem> = from_str(CONTENTS).unwrap();
+    assert_eq!(systems.len(), 2);
+    assert_eq!(systems["cent6_64"].status, Status::Legacy);
+    assert_eq!(systems["cent6_64"].secure, None);
+
+    let sections: HashMap<String, SectionOwned> = from_str(CONTENTS).unwrap();
+    assert_eq!(sections["cent6_64"].get("bits"), Some("64"));
+
+    let maps: HashMap<String, HashMap<String, String>> = from_str(CONTENTS).unwrap();
+    assert_eq!(maps["cent7_64"]["secure"], "yes");
+}
+
+#[test]
+fn from_str_given_bad_number_names_section_and_key() {
+    let error = from_str::<Systems>("[cent7_64]\nbits = lots\n").unwrap_err();
+    assert!(matches!(
+        error,
+        Error::InvalidValue { section: Some(section), key, value, target }
+            if section == "cent7_64" && key == "bits" && value == "lots" && target == "u8"
+    ));
+}
+
+#[test]
+fn from_str_given_unknown_variant_names_section_and_key() {
+    let contents = CONTENTS.replace("status = Current", "status = Retired");
+    let error = from_str::<Systems>(&contents).unwrap_err();
+    assert!(matches!(
+        &error,
+        Error::Serde { section: Some(section), key: Some(key), .. }
+            if section == "cent7_64" && key == "status"
+    ));
+    assert!(error.to_string().contains("Retired"));
+}
+
+#[test]
+fn from_str_given_missing_field_names_section() {
+    let error = from_str::<Systems>("[cent7_64]\nbits = 64\n").unwrap_err();
+    assert!(matches!(
+        &error,
+        Error::Serde { section: Some(section), key: None, .. } if section == "cent7_64"
+    ));
+    assert_eq!(
+        error.to_string(),
+        "missing field `architecture` in section 'cent7_64'"
+    );
+}
+
+#[test]
+fn from_str_given_list_and_inherited_keys_resolves_both() {
+    #[derive(Deserialize)]
+    struct Site {
+        hosts: Vec<String>,
+        ports: Vec<u16>,
+        bits: u8,
+    }
+
+    let contents = "[DEFAULT]\nbits = 64\n\n[site]\nhosts = lichost1,lichost2\nports = 80,443\n";
+    let opts = ParserOptions::new().default_section("DEFAULT");
+    let sites: HashMap<String, Site> = from_str_with(contents, &opts).unwrap();
+    let site = &sites["site"];
+    assert_eq!(site.hosts, vec!["lichost1", "lichost2"]);
+    assert_eq!(site.ports, vec![80, 443]);
+    assert_eq!(site.bits, 64);
+}
+
+#[test]
+fn from_str_with_given_list_separator_splits_on_it() {
+    #[derive(Debug, PartialEq, Deserialize, serde::Serialize)]
+    struct Site {
+        hosts: Vec<String>,
+        big: i128,
+        bigger: u128,
+    }
+
+    let contents = "[site]\nhosts = lichost1;lichost2\nbig = -170141183460469231731687303715884105728\nbigger = 340282366920938463463374607431768211455\n";
+    let opts = ParserOptions::new().list_separator(";");
+    let sites: HashMap<String, Site> = from_str_with(contents, &opts).unwrap();
+    let site = &sites["site"];
+    assert_eq!(site.hosts, vec!["lichost1", "lichost2"]);
+    assert_eq!(site.big, i128::MIN);
+    assert_eq!(site.bigger, u128::MAX);
+
+    // the serializer and deserializer agree on the separator
+    let format = crate::FormatOptions::new().list_separator(";");
+    let text = crate::ser::to_string_with(&sites, &format).unwrap();
+    assert_eq!(
+        from_str_with::<HashMap<String, Site>>(&text, &opts).unwrap(),
+        sites
+    );
+
+    let error =
+        from_str::<HashMap<String, Site>>("[site]\nhosts = a\nbig = 1\nbigger = -1\n").unwrap_err();
+    assert!(matches!(
+        error,
+        Error::InvalidValue { key, target, .. } if key == "bigger" && target == "u128"
+    ));
+}
+
+#[test]
+fn from_file_given_example_data_reads_sections() {
+    let mut cfgpath = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    cfgpath.push("example_data");
+    cfgpath.push("operating_systems.cfg");
+    let sections: HashMap<String, HashMap<String, String>> = from_file(cfgpath).unwrap();
+    assert_eq!(sections["cent7_64"]["python_version"], "2.7");
+}
@@ -0,0 +1,68 @@
+use super::*;
+use std::path::PathBuf;
+
+#[test]
+fn given_error_with_path_renders_path() {
+    let error =
+        Error::InvalidKey(Location::new(12, "  bad-key = b", 2).with_length(7)).with_path("os.cfg");
+    let rendered = Diagnostic::from(&error).render(Style::Plain);
+    let expected = r#"error: invalid key
+  --> os.cfg:12:3
+   |
+12 |   bad-key = b
+   |   ^^^^^^^
+   = hint: keys start with a letter, followed by letters, numbers and single underscores
+"#;
+    assert_eq!(rendered, expected);
+}
+
+#[test]
+fn given_tab_in_source_line_caret_keeps_tab() {
+    let location = Location::new(1, "\tkey value", 5);
+    assert_eq!(caret_padding(&location), "\t    ");
+}
+
+#[test]
+fn given_error_without_location_renders_message() {
+    let error = Error::Io {
+        path: None,
+        source: std::io::Error::new(std::io::ErrorKind::NotFound, "not found"),
+    };
+    let rendered = Diagnostic::from(&error).render(Style::Plain);
+    assert_eq!(rendered, "error: unable to read cfg: not found\n");
+}
+
+#[test]
+fn given_colored_style_renders_escape_codes() {
+    let mut location = Location::new(1, "[]", 1);
+    location.path = Some(PathBuf::from("os.cfg"));
+    let rendered = Diagnostic::from(&Error::InvalidHeader(location)).render(Style::Colored);
+    assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m"));
+    assert!(rendered.contains("\x1b[1;31m^\x1b[0m"));
+}
+
+#[test]
+fn given_header_without_closing_bracket_hints_at_bracket() {
+    let error = Error::InvalidHeader(Location::new(1, "[b", 1));
+    let diagnostic = Diagnostic::from(&error);
+    assert_eq!(
+        diagnostic.hint.as_deref(),
+        Some("close the section header with ']'")
+    );
+}
+
+#[test]
+fn given_header_with_bad_name_hints_at_charset() {
+    let error = Error::InvalidHeader(Location::new(1, "[1b]", 2));
+    let diagnostic = Diagnostic::from(&error);
+    assert!(diagnostic.hint.unwrap().starts_with("section names start"));
+}
+
+#[test]
+fn given_unclosed_header_in_cfg_hints_at_bracket() {
+    let error = crate::Config::parse_cfg_from_str("[a]\nx = 1\n[b\n").unwrap_err();
+    assert_eq!(
+        Diagnostic::from(&error).hint.as_deref(),
+        Some("close the section header with ']'")
+    );
+}
@@ -0,0 +1,247 @@
+use super::*;
+use crate::Dialect;
+
+const HAND_WRITTEN: &str = "# operating systems\r\n\
+                            [cent7_64]  # current\r\n\
+                            \tbits =  64\r\n\
+                            label = \"CentOS 7\"   # quoted\r\n\
+                            \r\n\
+                            # legacy\r\n\
+                            [cent6_64]\r\n\
+                            bits=32";
+
+fn inline_comments() -> ParserOptions {
+    ParserOptions::new().inline_comment_prefixes(vec!["#"])
+}
+
+#[test]
+fn given_no_edits_output_is_byte_identical() {
+    let doc = Document::parse_with(HAND_WRITTEN, &inline_comments()).unwrap();
+    assert_eq!(doc.to_string(), HAND_WRITTEN);
+
+    let contents = "\n\n[os]\nbits = 64\n\n\n";
+    let doc = Document::parse_cfg_from_str(contents).unwrap();
+    assert_eq!(doc.to_string(), contents);
+}
+
+#[test]
+fn given_invalid_cfg_parse_fails() {
+    let result = Document::parse_cfg_from_str("[os]\nbits 64\n");
+    assert!(matches!(result, Err(Error::MissingDelimiter(_))));
+}
+
+#[test]
+fn given_existing_key_set_replaces_only_the_value() {
+    let mut doc = Document::parse_with(HAND_WRITTEN, &inline_comments()).unwrap();
+    doc.set("cent7_64", "label", "CentOS 7.9").unwrap();
+    doc.set("cent7_64", "bits", "32").unwrap();
+    let expected = HAND_WRITTEN
+        .replace("\"CentOS 7\"", "\"CentOS 7.9\"")
+        .replace("\tbits =  64", "\tbits =  32");
+    assert_eq!(doc.to_string(), expected);
+    assert_eq!(doc.get("cent7_64", "label"), Some("CentOS 7.9".to_string()));
+}
+
+#[test]
+fn given_new_key_set_adds_it_after_the_last_key() {
+    let mut doc = Document::parse_with(HAND_WRITTEN, &inline_comments()).unwrap();
+    doc.set("cent7_64", "arch", "x86_64").unwrap();
+    doc.set("cent6_64", "arch", "x86").unwrap();
+    let expected = HAND_WRITTEN
+        .replace("# quoted\r\n", "# quoted\r\narch = x86_64\r\n")
+        .replace("bits=32", "bits=32\r\narch=x86");
+    assert_eq!(doc.to_string(), expected);
+}
+
+#[test]
+fn given_new_section_set_appends_it() {
+    let mut doc = Document::parse_cfg_from_str("[os]\nbits = 64\n").unwrap();
+    doc.set("arch", "name", "x86").unwrap();
+    assert_eq!(doc.to_string(), "[os]\nbits = 64\n\n[arch]\nname = x86\n");
+
+    let mut doc = Document::parse_cfg_from_str("").unwrap();
+    doc.set("os", "label", "Linux CentOS").unwrap();
+    assert_eq!(doc.to_string(), "[os]\nlabel = \"Linux CentOS\"\n");
+}
+
+#[test]
+fn given_invalid_name_set_fails() {
+    let mut doc = Document::parse_cfg_from_str("[os]\nbits = 64\n").unwrap();
+    let result = doc.set("os", "bad key", "1");
+    assert!(matches!(result, Err(Error::InvalidName(name)) if name == "bad key"));
+    assert_eq!(doc.to_string(), "[os]\nbits = 64\n");
+}
+
+#[test]
+fn given_permissive_names_set_accepts_them() {
+    let opts = ParserOptions::from(Dialect::WindowsIni);
+    let mut doc = Document::parse_with("[My App]\nWindow.Width=1024\n", &opts).unwrap();
+    doc.set("My App", "window.width", "800").unwrap();
+    doc.set("My App", "spell-check", "on").unwrap();
+    assert_eq!(
+        doc.to_string(),
+        "[My App]\nWindow.Width=800\nspell-check=on\n"
+    );
+    assert!(doc.set("My App", "a=b", "1").is_err());
+}
+
+#[test]
+fn given_key_insert_after_places_it_below() {
+    let contents = "[os]\nbits = 64  # word size\nlabel = centos\n";
+    let mut doc = Document::parse_with(contents, &inline_comments()).unwrap();
+    assert!(doc.insert_after("os", "bits", "arch", "x86").unwrap());
+    assert_eq!(
+        doc.to_string(),
+        "[os]\nbits = 64  # word size\narch = x86\nlabel = centos\n"
+    );
+
+    // an existing key is moved
+    assert!(doc.insert_after("os", "bits", "label", "rhel").unwrap());
+    assert_eq!(
+        doc.to_string(),
+        "[os]\nbits = 64  # word size\nlabel = rhel\narch = x86\n"
+    );
+
+    assert!(!doc.insert_after("os", "missing", "arch", "x86").unwrap());
+}
+
+#[test]
+fn given_key_remove_takes_its_continuation_lines() {
+    let opts = ParserOptions::from(Dialect::Python);
+    let contents = "[hosts]\nservers = lichost1\n  lichost2\n; backup\nport = 80";
+    let mut doc = Document::parse_with(contents, &opts).unwrap();
+    assert_eq!(
+        doc.get("hosts", "servers"),
+        Some("lichost1\nlichost2".to_string())
+    );
+    assert_eq!(
+        doc.remove("hosts", "servers"),
+        Some("lichost1\nlichost2".to_string())
+    );
+    assert_eq!(doc.to_string(), "[hosts]\n; backup\nport = 80");
+
+    // removing the last line keeps the missing newline missing
+    assert_eq!(doc.remove("hosts", "port"), Some("80".to_string()));
+    assert_eq!(doc.to_string(), "[hosts]\n; backup");
+    assert_eq!(doc.remove("hosts", "port"), None);
+}
+
+#[test]
+fn given_blank_line_within_continuation_edits_take_the_whole_value() {
+    let opts = ParserOptions::from(Dialect::Python);
+    let contents = "[hosts]\nservers = lichost1\n  lichost2\n\n  lichost3\n\nport = 80\n";
+    let mut doc = Document::parse_with(contents, &opts).unwrap();
+    assert_eq!(
+        doc.get("hosts", "servers"),
+        Some("lichost1\nlichost2\n\nlichost3".to_string())
+    );
+
+    doc.set("hosts", "servers", "lichost4").unwrap();
+    assert_eq!(
+        doc.to_string(),
+        "[hosts]\nservers = lichost4\n\nport = 80\n"
+    );
+}
+
+#[test]
+fn given_comment_within_continuation_edits_take_the_whole_value() {
+    let opts = ParserOptions::from(Dialect::Python);
+    let contents = "[hosts]\nservers = lichost1\n; spare\n  lichost2\n; backup\nport = 80\n";
+    let mut doc = Document::parse_with(contents, &opts).unwrap();
+    assert_eq!(
+        doc.get("hosts", "servers"),
+        Some("lichost1\nlichost2".to_string())
+    );
+
+    doc.set("hosts", "servers", "lichost3").unwrap();
+    assert_eq!(
+        doc.to_string(),
+        "[hosts]\nservers = lichost3\n; backup\nport = 80\n"
+    );
+    let text = doc.to_string();
+    let config = Config::parse_with(&text, &opts).unwrap();
+    assert_eq!(config.get_value("hosts", "servers"), Some("lichost3"));
+    assert_eq!(config.get_value("hosts", "port"), Some("80"));
+
+    let mut doc = Document::parse_with(contents, &opts).unwrap();
+    doc.remove("hosts", "servers");
+    assert_eq!(doc.to_string(), "[hosts]\n; backup\nport = 80\n");
+    let text = doc.to_string();
+    let config = Config::parse_with(&text, &opts).unwrap();
+    assert_eq!(config.get_value("hosts", "servers"), None);
+    assert_eq!(config.get_value("hosts", "port"), Some("80"));
+}
+
+#[test]
+fn given_continued_value_set_replaces_every_line() {
+    let opts = ParserOptions::new().continuation(Continuation::Backslash);
+    let contents = "[hosts]\nservers = lichost1\\\nlichost2\nport = 80\n";
+    let mut doc = Document::parse_with(contents, &opts).unwrap();
+    doc.set("hosts", "servers", "lichost3").unwrap();
+    assert_eq!(doc.to_string(), "[hosts]\nservers = lichost3\nport = 80\n");
+}
+
+#[test]
+fn given_section_rename_changes_only_the_header() {
+    let mut doc = Document::parse_with(HAND_WRITTEN, &inline_comments()).unwrap();
+    assert!(doc.rename_section("cent7_64", "centos7").unwrap());
+    assert_eq!(
+        doc.to_string(),
+        HAND_WRITTEN.replace("[cent7_64]", "[centos7]")
+    );
+    assert_eq!(doc.get("centos7", "bits"), Some("64".to_string()));
+    assert!(!doc.rename_section("missing", "other").unwrap());
+}
+
+#[test]
+fn given_inheritance_rename_updates_children() {
+    let opts = ParserOptions::new().inheritance(true);
+    let contents = "[base]\nbits = 64\n\n[cent7_64 : base]\narch = x86\n\n[cent6_64]\ninherits = base\n\n[other]\nnote = base\n";
+    let mut doc = Document::parse_with(contents, &opts).unwrap();
+    assert!(doc.rename_section("base", "linux").unwrap());
+    assert_eq!(
+        doc.to_string(),
+        "[linux]\nbits = 64\n\n[cent7_64 : linux]\narch = x86\n\n[cent6_64]\ninherits = linux\n\n[other]\nnote = base\n"
+    );
+
+    let text = doc.to_string();
+    let config = Config::parse_with(&text, &opts).unwrap();
+    assert_eq!(config.get_value("cent7_64", "bits"), Some("64"));
+    assert_eq!(config.get_value("cent6_64", "bits"), Some("64"));
+    assert_eq!(config.get_value("other", "bits"), None);
+}
+
+#[test]
+fn given_taken_name_rename_fails() {
+    let mut doc = Document::parse_with(HAND_WRITTEN, &inline_comments()).unwrap();
+    let result = doc.rename_section("cent7_64", "cent6_64");
+    assert!(matches!(
+        result,
+        Err(Error::DuplicateSection { name, location }) if name == "cent6_64" && location.line == 7
+    ));
+    assert_eq!(doc.to_string(), HAND_WRITTEN);
+}
+
+#[test]
+fn given_global_section_set_edits_keys_before_the_first_header() {
+    let opts = ParserOptions::new().global_section("global");
+    let contents = "# top\nuser = fred\n\n[os]\nbits = 64\n";
+    let mut doc = Document::parse_with(contents, &opts).unwrap();
+    doc.set("global", "host", "lichost").unwrap();
+    assert_eq!(
+        doc.to_string(),
+        "# top\nuser = fred\nhost = lichost\n\n[os]\nbits = 64\n"
+    );
+    assert_eq!(doc.get("global", "user"), Some("fred".to_string()));
+}
+
+#[test]
+fn given_edits_output_reparses() {
+    let mut doc = Document::parse_with(HAND_WRITTEN, &inline_comments()).unwrap();
+    doc.set("cent6_64", "label", "it's \"old\"").unwrap();
+    doc.set("cent6_64", "empty", "").unwrap();
+    let text = doc.to_string();
+    let config = Config::parse_with(&text, &inline_comments()).unwrap();
+    assert_eq!(config.get_value("cent6_64", "label"), Some("it's \"old\""));
+    assert_eq!(config.get_value("cent6_64", "empty"), Some(""));
+}
@@ -0,0 +1,59 @@
+use super::*;
+
+fn source() -> HashMap<&'static str, &'static str> {
+    let mut source = HashMap::new();
+    source.insert("STUDIO_ROOT", "/studio");
+    source.insert("EMPTY", "");
+    source
+}
+
+#[test]
+fn given_bare_and_braced_variables_expands_both() {
+    let result = expand(&source(), "site", "root", "$STUDIO_ROOT/a:${STUDIO_ROOT}b");
+    assert_eq!(result.unwrap(), "/studio/a:/studiob");
+}
+
+#[test]
+fn given_default_uses_it_when_unset_or_empty() {
+    let result = expand(&source(), "site", "tmp", "${TMPDIR:-/tmp}|${EMPTY:-x}");
+    assert_eq!(result.unwrap(), "/tmp|x");
+    let result = expand(&source(), "site", "root", "${STUDIO_ROOT:-/x}");
+    assert_eq!(result.unwrap(), "/studio");
+}
+
+#[test]
+fn given_dollar_without_name_leaves_it_alone() {
+    let result = expand(&source(), "site", "price", "5$ and $$HOME and $-");
+    assert_eq!(result.unwrap(), "5$ and $HOME and $-");
+}
+
+#[test]
+fn given_undefined_variable_names_section_and_key() {
+    let result = expand(&source(), "site", "home", "$HOME/bin");
+    match result {
+        Err(error @ Error::UndefinedVariable { .. }) => assert_eq!(
+            error.to_string(),
+            "undefined variable 'HOME' in key 'home' of section 'site'"
+        ),
+        _ => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn given_malformed_reference_returns_error() {
+    for value in &["${HOME", "${}", "${1X}", "${A B}"] {
+        let result = expand(&source(), "site", "home", value);
+        assert!(
+            matches!(result, Err(Error::InvalidVariable { .. })),
+            "{}",
+            value
+        );
+    }
+}
+
+#[test]
+fn given_environment_reads_process_variables() {
+    std::env::set_var("CFGPARSER_TEST_VAR", "set");
+    let result = expand(&Environment, "site", "key", "${CFGPARSER_TEST_VAR}");
+    assert_eq!(result.unwrap(), "set");
+}
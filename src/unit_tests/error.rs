@@ -0,0 +1,46 @@
+use super::*;
+
+mod location {
+    use super::*;
+
+    #[test]
+    fn given_byte_offset_calculates_char_column() {
+        let location = Location::new(3, "nämé = x", 7);
+        assert_eq!(location.line, 3);
+        assert_eq!(location.column, 6);
+        assert_eq!(location.source_line, "nämé = x");
+    }
+
+    #[test]
+    fn given_path_displays_path_line_and_column() {
+        let mut location = Location::new(2, "[bad header]", 5);
+        location.path = Some(PathBuf::from("os.cfg"));
+        assert_eq!(location.to_string(), "os.cfg:2:6");
+    }
+}
+
+mod error {
+    use super::*;
+
+    #[test]
+    fn given_with_path_sets_location_path() {
+        let error = Error::InvalidKey(Location::new(1, "-a = b", 0)).with_path("os.cfg");
+        assert_eq!(
+            error.location().unwrap().path,
+            Some(PathBuf::from("os.cfg"))
+        );
+        assert_eq!(error.to_string(), "os.cfg:1:1: invalid key");
+    }
+
+    #[test]
+    fn given_io_error_displays_path() {
+        let error = Error::Io {
+            path: None,
+            source: io::Error::new(io::ErrorKind::NotFound, "not found"),
+        }
+        .with_path("missing.cfg");
+        assert!(error
+            .to_string()
+            .starts_with("missing.cfg: unable to read cfg"));
+    }
+}
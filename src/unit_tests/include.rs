@@ -0,0 +1,145 @@
+use super::*;
+use crate::{from_path_with, DuplicatePolicy};
+use std::fs;
+
+// a scratch directory holding the supplied files, removed when dropped
+struct Scratch(PathBuf);
+
+impl Scratch {
+    fn new(name: &str, files: &[(&str, &str)]) -> Self {
+        let dir =
+            std::env::temp_dir().join(format!("cfgparser-include-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (file, contents) in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        Self(dir)
+    }
+
+    fn path(&self, file: &str) -> PathBuf {
+        self.0.join(file)
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn opts() -> ParserOptions {
+    ParserOptions::new().includes(true)
+}
+
+#[test]
+fn given_relative_include_splices_file_and_records_origins() {
+    let scratch = Scratch::new(
+        "relative",
+        &[
+            ("main.cfg", "%include sub/base.cfg\n[site]\nname = pv\n"),
+            ("sub/base.cfg", "[os]\n\nbits = 64\n"),
+        ],
+    );
+    let config = from_path_with(scratch.path("main.cfg"), &opts()).unwrap();
+    assert_eq!(config.get_value("os", "bits"), Some("64"));
+    let origin = config.origin("os", "bits").unwrap();
+    assert_eq!(origin.path, Some(scratch.path("sub/base.cfg")));
+    assert_eq!(origin.line, 3);
+    let origin = config.origin("site", "name").unwrap();
+    assert_eq!(
+        origin.to_string(),
+        format!("{}:3", scratch.path("main.cfg").display())
+    );
+}
+
+#[test]
+fn given_glob_include_splices_matches_in_sorted_order() {
+    let scratch = Scratch::new(
+        "glob",
+        &[
+            ("main.cfg", "[os]\nbits = 32\n@include conf.d/*.cfg\n"),
+            ("conf.d/20-late.cfg", "[os]\nbits = 128\n"),
+            ("conf.d/10-early.cfg", "[os]\nbits = 64\nstatus = Current\n"),
+            ("conf.d/ignored.txt", "[os]\nbits = 0\n"),
+        ],
+    );
+    let opts = opts().duplicate_sections(DuplicatePolicy::Merge);
+    let config = from_path_with(scratch.path("main.cfg"), &opts).unwrap();
+    assert_eq!(config.get_value("os", "bits"), Some("128"));
+    assert_eq!(config.get_value("os", "status"), Some("Current"));
+    let origin = config.origin("os", "status").unwrap();
+    assert_eq!(origin.path, Some(scratch.path("conf.d/10-early.cfg")));
+}
+
+#[test]
+fn given_glob_without_matches_includes_nothing() {
+    let scratch = Scratch::new("empty-glob", &[("main.cfg", "%include conf.d/*.cfg\n")]);
+    let config = from_path_with(scratch.path("main.cfg"), &opts()).unwrap();
+    assert!(config.get("os").is_none());
+}
+
+#[test]
+fn given_include_cycle_returns_error() {
+    let scratch = Scratch::new(
+        "cycle",
+        &[
+            ("a.cfg", "%include b.cfg\n"),
+            ("b.cfg", "[os]\nbits = 64\n%include a.cfg\n"),
+        ],
+    );
+    let result = from_path_with(scratch.path("a.cfg"), &opts());
+    match result {
+        Err(Error::IncludeCycle { chain, location }) => {
+            assert_eq!(chain.len(), 3);
+            assert_eq!(chain.first(), chain.last());
+            assert_eq!(location.path, Some(scratch.path("b.cfg")));
+            assert_eq!(location.line, 3);
+        }
+        _ => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn given_missing_include_returns_io_error() {
+    let scratch = Scratch::new("missing", &[("main.cfg", "%include nope.cfg\n")]);
+    let result = from_path_with(scratch.path("main.cfg"), &opts());
+    match result {
+        Err(Error::Io { path, .. }) => assert_eq!(path, Some(scratch.path("nope.cfg"))),
+        _ => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn given_error_in_included_file_reports_its_path_and_line() {
+    let scratch = Scratch::new(
+        "error",
+        &[
+            ("main.cfg", "[site]\nname = pv\n%include bad.cfg\n"),
+            ("bad.cfg", "[os]\nbits 64\n"),
+        ],
+    );
+    let result = from_path_with(scratch.path("main.cfg"), &opts());
+    match result {
+        Err(Error::MissingDelimiter(location)) => {
+            assert_eq!(location.path, Some(scratch.path("bad.cfg")));
+            assert_eq!(location.line, 2);
+        }
+        _ => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn given_includes_disabled_directive_is_an_error() {
+    let scratch = Scratch::new("disabled", &[("main.cfg", "[os]\n%include other.cfg\n")]);
+    let result = from_path_with(scratch.path("main.cfg"), &ParserOptions::new());
+    assert!(matches!(result, Err(Error::InvalidKey(_))));
+}
+
+#[test]
+fn directive_requires_whitespace_after_keyword() {
+    assert_eq!(directive("  %include  a.cfg "), Some("a.cfg"));
+    assert_eq!(directive("@include"), Some(""));
+    assert_eq!(directive("%included = a"), None);
+}
@@ -0,0 +1,131 @@
+use super::*;
+use crate::{Config, ParserOptions};
+
+fn interpolate(contents: &str, syntax: Interpolation, key: &str) -> Result<Option<String>, Error> {
+    let config = Config::parse_cfg_from_str(contents).unwrap();
+    config.get_interpolated("os", key, &Interpolator::new(syntax))
+}
+
+mod extended {
+    use super::*;
+
+    #[test]
+    fn given_nested_references_expands_each() {
+        let contents = "[os]\nname = cent7\narch = linux_${name}\nlegacy = ${arch}_x86_64\n";
+        let result = interpolate(contents, Interpolation::Extended, "legacy");
+        assert_eq!(result.unwrap(), Some("linux_cent7_x86_64".to_string()));
+    }
+
+    #[test]
+    fn given_section_reference_expands_from_other_section() {
+        let contents = "[os]\nlegacy = ${old:arch}\n[old]\narch = cent6\n";
+        let result = interpolate(contents, Interpolation::Extended, "legacy");
+        assert_eq!(result.unwrap(), Some("cent6".to_string()));
+    }
+
+    #[test]
+    fn given_double_dollar_yields_literal_dollar() {
+        let contents = "[os]\nprice = $$5\n";
+        let result = interpolate(contents, Interpolation::Extended, "price");
+        assert_eq!(result.unwrap(), Some("$5".to_string()));
+    }
+
+    #[test]
+    fn given_missing_key_returns_none() {
+        let contents = "[os]\nname = cent7\n";
+        let result = interpolate(contents, Interpolation::Extended, "other");
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[test]
+    fn given_unterminated_reference_returns_error() {
+        let contents = "[os]\nname = ${arch\n";
+        let result = interpolate(contents, Interpolation::Extended, "name");
+        match result {
+            Err(Error::InvalidInterpolation { chain }) => assert_eq!(chain, vec!["os:name"]),
+            _ => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn given_missing_reference_returns_chain() {
+        let contents = "[os]\na = ${b}\nb = ${c}\n";
+        let result = interpolate(contents, Interpolation::Extended, "a");
+        match result {
+            Err(Error::MissingReference { chain }) => {
+                assert_eq!(chain, vec!["os:a", "os:b", "os:c"])
+            }
+            _ => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn given_cycle_returns_chain() {
+        let contents = "[os]\na = ${b}\nb = ${other:c}\n[other]\nc = ${os:a}\n";
+        let result = interpolate(contents, Interpolation::Extended, "a");
+        match result {
+            Err(error @ Error::InterpolationCycle { .. }) => assert_eq!(
+                error.to_string(),
+                "interpolation cycle os:a -> os:b -> other:c -> os:a"
+            ),
+            _ => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn given_references_beyond_max_depth_returns_error() {
+        let contents = "[os]\na = ${b}\nb = ${c}\nc = ${d}\nd = done\n";
+        let config = Config::parse_cfg_from_str(contents).unwrap();
+        let interpolator = Interpolator::new(Interpolation::Extended).max_depth(3);
+        let result = config.get_interpolated("os", "a", &interpolator);
+        match result {
+            Err(Error::InterpolationDepth { chain }) => assert_eq!(chain.len(), 4),
+            _ => panic!("unexpected result {:?}", result),
+        }
+        let interpolator = interpolator.max_depth(4);
+        let result = config.get_interpolated("os", "a", &interpolator);
+        assert_eq!(result.unwrap(), Some("done".to_string()));
+    }
+}
+
+mod basic {
+    use super::*;
+
+    #[test]
+    fn given_reference_expands_from_same_section() {
+        let contents = "[os]\nname = cent7\narch = linux_%(name)s_x86_64\n";
+        let result = interpolate(contents, Interpolation::Basic, "arch");
+        assert_eq!(result.unwrap(), Some("linux_cent7_x86_64".to_string()));
+    }
+
+    #[test]
+    fn given_double_percent_yields_literal_percent() {
+        let contents = "[os]\nload = 50%%\n";
+        let result = interpolate(contents, Interpolation::Basic, "load");
+        assert_eq!(result.unwrap(), Some("50%".to_string()));
+    }
+
+    #[test]
+    fn given_extended_syntax_leaves_it_alone() {
+        let contents = "[os]\nname = ${arch}\n";
+        let result = interpolate(contents, Interpolation::Basic, "name");
+        assert_eq!(result.unwrap(), Some("${arch}".to_string()));
+    }
+
+    #[test]
+    fn given_missing_conversion_returns_error() {
+        let contents = "[os]\nname = %(arch)\narch = x\n";
+        let result = interpolate(contents, Interpolation::Basic, "name");
+        assert!(matches!(result, Err(Error::InvalidInterpolation { .. })));
+    }
+
+    #[test]
+    fn given_default_section_references_fall_back() {
+        let contents = "[DEFAULT]\nroot = /studio\n\n[os]\npath = %(root)s/os\n";
+        let opts = ParserOptions::new().default_section("DEFAULT");
+        let config = Config::parse_with(contents, &opts).unwrap().to_owned();
+        let interpolator = Interpolator::new(Interpolation::Basic);
+        let result = config.get_interpolated("os", "path", &interpolator);
+        assert_eq!(result.unwrap(), Some("/studio/os".to_string()));
+    }
+}
@@ -0,0 +1,139 @@
+use super::*;
+use crate::Config;
+
+fn parse(contents: &str) -> ConfigOwned {
+    let opts = ParserOptions::new().tombstones(true);
+    Config::parse_with(contents, &opts).unwrap().to_owned()
+}
+
+mod merge {
+    use super::*;
+
+    #[test]
+    fn given_override_later_keys_win() {
+        let mut config = parse("[os]\na = 1\nb = 2\n");
+        config.merge(
+            parse("[os]\nb = 3\nc = 4\n[new]\nd = 5\n"),
+            MergeStrategy::Override,
+        );
+        assert_eq!(config.get_value("os", "a"), Some("1"));
+        assert_eq!(config.get_value("os", "b"), Some("3"));
+        assert_eq!(config.get_value("os", "c"), Some("4"));
+        assert_eq!(config.get_value("new", "d"), Some("5"));
+        assert_eq!(config.origin("os", "b").unwrap().line, 2);
+    }
+
+    #[test]
+    fn given_preserve_earlier_keys_win() {
+        let mut config = parse("[os]\na = 1\n");
+        config.merge(parse("[os]\na = 2\nb = 3\n"), MergeStrategy::Preserve);
+        assert_eq!(config.get_value("os", "a"), Some("1"));
+        assert_eq!(config.get_value("os", "b"), Some("3"));
+    }
+
+    #[test]
+    fn given_replace_sections_drops_earlier_keys() {
+        let mut config = parse("[os]\na = 1\n[other]\nx = 1\n");
+        config.merge(parse("[os]\nb = 2\n"), MergeStrategy::ReplaceSections);
+        assert_eq!(config.get_value("os", "a"), None);
+        assert_eq!(config.get_value("os", "b"), Some("2"));
+        assert_eq!(config.get_value("other", "x"), Some("1"));
+    }
+
+    #[test]
+    fn given_tombstones_removes_keys_and_sections() {
+        let mut config = parse("[os]\na = 1\nb = 2\n[old]\nx = 1\n[replaced]\ny = 1\n");
+        config.merge(
+            parse("[os]\n!a\n[!old]\n[!replaced]\nz = 2\n"),
+            MergeStrategy::Override,
+        );
+        assert_eq!(config.get_value("os", "a"), None);
+        assert_eq!(config.get_value("os", "b"), Some("2"));
+        assert!(config.get("old").is_none());
+        assert_eq!(config.get_value("replaced", "y"), None);
+        assert_eq!(config.get_value("replaced", "z"), Some("2"));
+        assert!(!config.section("replaced").unwrap().replaces());
+    }
+
+    #[cfg(feature = "ordered")]
+    #[test]
+    fn given_ordered_feature_tombstones_keep_the_order() {
+        let mut config = parse("[os]\na = 1\nb = 2\nc = 3\nd = 4\n!a\n");
+        let keys = |config: &ConfigOwned| {
+            config
+                .section("os")
+                .unwrap()
+                .items()
+                .map(|(key, _)| key)
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        assert_eq!(keys(&config), "b,c,d");
+
+        config.merge(parse("[os]\n!b\n"), MergeStrategy::Override);
+        assert_eq!(keys(&config), "c,d");
+
+        let mut config = parse("[w]\n[x]\n[y]\n[z]\n");
+        config.merge(parse("[!w]\n"), MergeStrategy::Override);
+        let names = config
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["x", "y", "z"]);
+    }
+}
+
+mod layered_config {
+    use super::*;
+
+    fn layers() -> LayeredConfig {
+        let mut layers = LayeredConfig::new();
+        layers.push("system", parse("[os]\na = 1\nb = 1\nc = 1\n[old]\nx = 1\n"));
+        layers.push("site", parse("[os]\nb = 2\n!c\n"));
+        layers.push("user", parse("[!old]\n[os]\nd = 3\n"));
+        layers
+    }
+
+    #[test]
+    fn lookup_reports_supplying_layer() {
+        let layers = layers();
+        let found = layers.lookup("os", "a").unwrap();
+        assert_eq!((found.value, found.layer), ("1", "system"));
+        assert_eq!(found.origin.unwrap().line, 2);
+        let found = layers.lookup("os", "b").unwrap();
+        assert_eq!((found.value, found.layer), ("2", "site"));
+        assert_eq!(layers.get_value("os", "d"), Some("3"));
+    }
+
+    #[test]
+    fn lookup_stops_at_tombstones() {
+        let layers = layers();
+        assert_eq!(layers.get_value("os", "c"), None);
+        assert_eq!(layers.get_value("old", "x"), None);
+        assert_eq!(layers.get_value("missing", "x"), None);
+    }
+
+    #[test]
+    fn merged_matches_lookups() {
+        let layers = layers();
+        let merged = layers.merged();
+        for key in &["a", "b", "c", "d"] {
+            assert_eq!(merged.get_value("os", key), layers.get_value("os", key));
+        }
+        assert!(merged.get("old").is_none());
+        assert!(layers.layer("site").is_some());
+        let names = layers.layers().map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(names, vec!["system", "site", "user"]);
+    }
+
+    #[test]
+    fn lookup_folds_keys_of_folded_layers() {
+        let opts = ParserOptions::new().tombstones(true).fold_case(true);
+        let mut layers = LayeredConfig::new();
+        layers.push("system", parse("[os]\nBits = 64\nArch = x86\n"));
+        let user = Config::parse_with("[os]\nBits = 32\n!Arch\n", &opts).unwrap();
+        layers.push("user", user.to_owned());
+        assert_eq!(layers.get_value("os", "BITS"), Some("32"));
+        assert_eq!(layers.get_value("os", "Arch"), None);
+    }
+}
@@ -0,0 +1,140 @@
+use super::*;
+use crate::{Config, Section};
+
+#[test]
+fn given_default_options_is_strict_dialect() {
+    assert_eq!(
+        ParserOptions::new(),
+        ParserOptions::dialect(Dialect::Strict)
+    );
+    assert_eq!(
+        ParserOptions::from(Dialect::Python),
+        ParserOptions::dialect(Dialect::Python)
+    );
+}
+
+#[test]
+fn given_python_dialect_can_parse_configparser_file() {
+    let contents = r#"
+# generated by os_tools
+[Cent7_64]
+Label: Linux CentOS 7 (64 bit)
+; retired hosts remain below
+hosts = lichost1
+    lichost2
+
+[future_os]
+"#;
+    let opts = ParserOptions::dialect(Dialect::Python);
+    let config = Config::parse_with(contents, &opts).unwrap();
+    let mut expected = Section::new("Cent7_64");
+    expected.insert("label", "Linux CentOS 7 (64 bit)");
+    expected.insert("hosts", "lichost1\nlichost2".to_string());
+    assert_eq!(config.section("Cent7_64"), Some(&expected));
+    assert_eq!(
+        config.section("future_os"),
+        Some(&Section::new("future_os"))
+    );
+}
+
+#[test]
+fn given_windows_ini_dialect_merges_sections_and_keeps_first_key() {
+    let contents =
+        "; settings\r\n[Display]\r\nWidth = 1024\r\n[Display]\r\nwidth = 800\r\nHeight = 768\r\n";
+    let opts = ParserOptions::dialect(Dialect::WindowsIni);
+    let config = Config::parse_with(contents, &opts).unwrap();
+    let mut expected = Section::new("Display");
+    expected.insert("width", "1024");
+    expected.insert("height", "768");
+    assert_eq!(config.section("Display"), Some(&expected));
+}
+
+#[test]
+fn given_python_dialect_can_parse_configparser_docs_example() {
+    let contents = r#"
+[DEFAULT]
+ServerAliveInterval = 45
+Compression = yes
+
+[forge.example]
+User = hg
+
+[Simple Values]
+key=value
+spaces in keys=allowed
+spaces in values=allowed as well
+spaces around the delimiter = obviously
+you can also use : to delimit keys from values
+empty string value here =
+
+[Multiline Values]
+chorus: I'm a lumberjack, and I'm okay
+    I sleep all night and I work all day
+
+[Sections Can Be Indented]
+    can_values_be_as_well = True
+    multiline_values = are
+        handled just fine as
+
+        long as they are indented
+    # Did I mention we can indent comments, too?
+"#;
+    let opts = ParserOptions::dialect(Dialect::Python);
+    let config = Config::parse_with(contents, &opts).unwrap();
+    assert_eq!(config.get_value("forge.example", "user"), Some("hg"));
+    assert_eq!(
+        config.get_value("forge.example", "serveraliveinterval"),
+        Some("45")
+    );
+    assert_eq!(
+        config.get_value("Simple Values", "spaces in keys"),
+        Some("allowed")
+    );
+    assert_eq!(
+        config.get_value("Simple Values", "spaces around the delimiter"),
+        Some("obviously")
+    );
+    assert_eq!(
+        config.get_value("Simple Values", "you can also use"),
+        Some("to delimit keys from values")
+    );
+    assert_eq!(
+        config.get_value("Simple Values", "empty string value here"),
+        Some("")
+    );
+    assert_eq!(
+        config.get_value("Multiline Values", "chorus"),
+        Some("I'm a lumberjack, and I'm okay\nI sleep all night and I work all day")
+    );
+    assert_eq!(
+        config.get_value("Sections Can Be Indented", "multiline_values"),
+        Some("are\nhandled just fine as\n\nlong as they are indented")
+    );
+}
+
+#[test]
+fn given_windows_ini_dialect_can_parse_ini_file() {
+    let contents = "; Application settings\r\n[My App]\r\nWindow.Width=1024\r\nRecent Files=C:\\Users\\me\\report.docx\r\n\r\n[Plugins]\r\nfoo.bar=1\r\nspell-check = enabled ; per user\r\n";
+    let opts = ParserOptions::dialect(Dialect::WindowsIni);
+    let config = Config::parse_with(contents, &opts).unwrap();
+    assert_eq!(config.get_value("My App", "window.width"), Some("1024"));
+    assert_eq!(
+        config.get_value("My App", "recent files"),
+        Some("C:\\Users\\me\\report.docx")
+    );
+    assert_eq!(config.get_value("Plugins", "foo.bar"), Some("1"));
+    assert_eq!(
+        config.get_value("Plugins", "spell-check"),
+        Some("enabled ; per user")
+    );
+}
+
+#[test]
+fn given_strict_names_rejects_permissive_names() {
+    let opts = ParserOptions::new().spaces_in_values(true);
+    assert!(Config::parse_with("[My App]\nx = 1\n", &opts).is_err());
+    assert!(Config::parse_with("[app]\nmy-key = 1\n", &opts).is_err());
+    let opts = opts.names(Names::Permissive);
+    let config = Config::parse_with("[My App]\nmy-key = 1\n", &opts).unwrap();
+    assert_eq!(config.get_value("My App", "my-key"), Some("1"));
+}
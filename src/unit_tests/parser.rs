@@ -12,7 +12,7 @@ mod header {
     #[test]
     fn given_nospaces_can_parse() {
         fn parser(input: &str) -> IResult<&str, &str> {
-            complete(header)(input)
+            complete(header(Names::Strict))(input)
         }
         let result = parser("[key]");
         assert_eq!(result, Ok(("", "key")));
@@ -21,7 +21,7 @@ mod header {
     #[test]
     fn given_spaces_can_parse() {
         fn parser(input: &str) -> IResult<&str, &str> {
-            complete(header)(input)
+            complete(header(Names::Strict))(input)
         }
         let result = parser(" [ key  ]    ");
         assert_eq!(result, Ok(("", "key")));
@@ -30,189 +30,77 @@ mod header {
     #[test]
     fn given_spaces_underscores_can_parse() {
         fn parser(input: &str) -> IResult<&str, &str> {
-            complete(header)(input)
+            complete(header(Names::Strict))(input)
         }
         let result = parser(" [ kEy_VAlue  ]    ");
         assert_eq!(result, Ok(("", "kEy_VAlue")));
     }
-}
-//
-// until_illegar_char tests
-//
-mod until_illegal_char {
-    use super::*;
 
     #[test]
-    fn given_valid_str_can_parse() {
+    fn given_tombstone_can_parse() {
         fn parser(input: &str) -> IResult<&str, &str> {
-            complete(until_illegal_char)(input)
-        }
-        let result = parser("this.is-_one1234567890$");
-        assert_eq!(result, Ok(("", "this.is-_one1234567890$")));
-    }
-
-    #[test]
-    fn given_partially_valid_str_can_parse() {
-        fn parser(input: &str) -> IResult<&str, &str> {
-            until_illegal_char(input)
-        }
-        let result = parser("this.is- _one1234567890$");
-        assert_eq!(result, Ok((" _one1234567890$", "this.is-")));
-    }
-}
-//
-// key_value_pair tests
-//
-mod key_value_pair {
-    use super::*;
-
-    #[test]
-    fn given_valid_input_can_parse() {
-        fn parser(input: &str) -> IResult<&str, (&str, &str)> {
-            key_value_pair(input)
-        }
-        let result = parser("this_key = val123-543_bla");
-        assert_eq!(result, Ok(("", ("this_key", "val123-543_bla"))));
-    }
-
-    #[test]
-    fn given_valid_input_starting_with_space_can_parse() {
-        fn parser(input: &str) -> IResult<&str, (&str, &str)> {
-            key_value_pair(input)
+            complete(tombstone_header(Names::Strict))(input)
         }
-        let result = parser("  this_key = val123-543_bla");
-        assert_eq!(result, Ok(("", ("this_key", "val123-543_bla"))));
+        assert_eq!(parser("[ ! cent6_64 ]"), Ok(("", "cent6_64")));
+        assert!(parser("[cent6_64]").is_err());
     }
 
     #[test]
-    fn given_valid_input2_can_parse() {
+    fn given_parent_can_parse() {
         fn parser(input: &str) -> IResult<&str, (&str, &str)> {
-            key_value_pair(input)
+            complete(inherit_header(Names::Strict))(input)
         }
-        let result = parser("another_key = 6053@lichost3-64:27--@bb-fooby.d4.com");
         assert_eq!(
-            result,
-            Ok(("", ("another_key", "6053@lichost3-64:27--@bb-fooby.d4.com")))
+            parser("[cent7_64 : cent6_64]"),
+            Ok(("", ("cent7_64", "cent6_64")))
         );
+        assert_eq!(parser("[a:b]  "), Ok(("", ("a", "b"))));
+        assert!(parser("[cent7_64]").is_err());
     }
-
     #[test]
-    fn given_invalid_input2_can_partially_parse() {
-        fn parser(input: &str) -> IResult<&str, (&str, &str)> {
-            key_value_pair(input)
+    fn given_permissive_names_can_parse() {
+        fn parser(input: &str) -> IResult<&str, &str> {
+            complete(header(Names::Permissive))(input)
         }
-        let result = parser("another_key = 6053 @lichost3-64:27--@bb-fooby.d4.com");
-        assert_eq!(
-            result,
-            Ok(("@lichost3-64:27--@bb-fooby.d4.com", ("another_key", "6053")))
-        );
+        assert_eq!(parser("[ My App ]"), Ok(("", "My App")));
+        assert_eq!(parser("[foo.bar-baz:1]"), Ok(("", "foo.bar-baz:1")));
+        assert!(parser("[ ]").is_err());
+        assert_eq!(parser("[a]b]"), Ok(("b]", "a")));
     }
+
     #[test]
-    fn given_invalid_input_can_partialy_parse() {
+    fn given_permissive_names_parent_can_parse() {
         fn parser(input: &str) -> IResult<&str, (&str, &str)> {
-            key_value_pair(input)
+            complete(inherit_header(Names::Permissive))(input)
         }
-        let result = parser("another_key = 6053#@lichost3-64:27--@bb-fooby.d4.com");
         assert_eq!(
-            result,
-            Ok((
-                "#@lichost3-64:27--@bb-fooby.d4.com",
-                ("another_key", "6053")
-            ))
+            parser("[My App : Base App]"),
+            Ok(("", ("My App", "Base App")))
         );
     }
 }
 //
-// key_value_pair_line
+// until_illegar_char tests
 //
-mod key_value_pair_line {
+mod until_illegal_char {
     use super::*;
-    #[test]
-    fn given_valid_input_ending_in_newline_can_parse() {
-        fn parser(input: &str) -> IResult<&str, (&str, &str)> {
-            key_value_pair_line(input)
-        }
-        let result = parser("this_key = val123-543_bla\n");
-        assert_eq!(result, Ok(("", ("this_key", "val123-543_bla"))));
-    }
 
     #[test]
-    fn given_valid_input_ending_with_spaces_can_parse() {
-        fn parser(input: &str) -> IResult<&str, (&str, &str)> {
-            key_value_pair_line(input)
+    fn given_valid_str_can_parse() {
+        fn parser(input: &str) -> IResult<&str, &str> {
+            complete(until_illegal_char)(input)
         }
-        let result = parser("  this_key = val123-543_bla   ");
-        assert_eq!(result, Ok(("", ("this_key", "val123-543_bla"))));
-    }
-}
-//
-// parse_section tests
-//
-mod parse_section {
-    use super::*;
-
-    #[test]
-    fn given_section_with_empty_lines_can_parse() {
-        let section = r#"
-    
-[test]
-this = is
-the = way
-
-"#;
-        let result = parse_section(section);
-        let mut expected = Section::new("test");
-        expected.insert("this", "is");
-        expected.insert("the", "way");
-        assert_eq!(result, Ok(("", expected)));
+        let result = parser("this.is-_one1234567890$");
+        assert_eq!(result, Ok(("", "this.is-_one1234567890$")));
     }
 
     #[test]
-    fn given_section_with_empty_lines_and_spaces_can_parse() {
-        let section = r#"
-    
- [test]
-  this = is
-   the = way
-
-"#;
-        let result = parse_section(section);
-        let mut expected = Section::new("test");
-        expected.insert("this", "is");
-        expected.insert("the", "way");
-        assert_eq!(result, Ok(("", expected)));
-    }
-}
-//
-// parse_sections tests
-//
-mod parse_sections {
-    use super::*;
-
-    #[test]
-    fn given_section_with_empty_lines_can_parse() {
-        let sections = r#"
-    
-[test]
-this = is
-the = way
-
-[test2]
-foo = is
-the = bar
-
-"#;
-        let result = parse_sections(sections);
-
-        let mut section1 = Section::new("test");
-        section1.insert("this", "is");
-        section1.insert("the", "way");
-
-        let mut section2 = Section::new("test2");
-        section2.insert("foo", "is");
-        section2.insert("the", "bar");
-
-        assert_eq!(result, Ok(("", vec![section1, section2])));
+    fn given_partially_valid_str_can_parse() {
+        fn parser(input: &str) -> IResult<&str, &str> {
+            until_illegal_char(input)
+        }
+        let result = parser("this.is- _one1234567890$");
+        assert_eq!(result, Ok((" _one1234567890$", "this.is-")));
     }
 }
 //
@@ -241,7 +129,7 @@ the = bar
         let mut section2 = Section::new("test2");
         section2.insert("foo", "is");
         section2.insert("the", "bar");
-        assert_eq!(result, Ok(("", vec![section1, section2])));
+        assert_eq!(result.unwrap(), vec![section1, section2]);
     }
 
     #[test]
@@ -264,7 +152,7 @@ the = way
         let mut section2 = Section::new("test2");
         section2.insert("foo", "is");
         section2.insert("the", "bar");
-        assert_eq!(result, Ok(("", vec![section1, section2])));
+        assert_eq!(result.unwrap(), vec![section1, section2]);
     }
 
     #[test]
@@ -285,6 +173,36 @@ the = bar"#;
         let mut section2 = Section::new("test2");
         section2.insert("foo", "is");
         section2.insert("the", "bar");
-        assert_eq!(result, Ok(("", vec![section1, section2])));
+        assert_eq!(result.unwrap(), vec![section1, section2]);
+    }
+
+    #[test]
+    fn given_empty_input_can_parse() {
+        assert!(parse_cfg_from_str("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn given_only_comments_can_parse() {
+        let result = parse_cfg_from_str("\n# nothing\n\n# to see here\n");
+        assert!(result.unwrap().is_empty());
+    }
+
+    #[test]
+    fn given_empty_sections_and_trailing_comments_can_parse() {
+        let result = parse_cfg_from_str("[future_os]\n[test]\nthis = is\n# one\n# two\n");
+        let mut section = Section::new("test");
+        section.insert("this", "is");
+        assert_eq!(result.unwrap(), vec![Section::new("future_os"), section]);
+    }
+
+    #[test]
+    fn given_quotes_and_bad_lines_agrees_with_config() {
+        let result = parse_cfg_from_str("[test]\nlabel = \"two words\"\n");
+        let mut section = Section::new("test");
+        section.insert("label", "two words");
+        assert_eq!(result.unwrap(), vec![section]);
+
+        let result = parse_cfg_from_str("[test]\nkey = two words\n");
+        assert!(matches!(result, Err(Error::IllegalCharacter { .. })));
     }
 }
@@ -97,3 +97,54 @@ mod space0_eol {
         assert_eq!(result, Ok(("", "# this is junk \n")));
     }
 }
+
+mod quoted_value {
+    use super::*;
+    use std::borrow::Cow;
+
+    #[test]
+    fn given_double_quoted_value_with_spaces_borrows() {
+        let result = quoted_value(r#""Linux CentOS 7 (64 bit)""#);
+        assert_eq!(result, Ok(("", Cow::Borrowed("Linux CentOS 7 (64 bit)"))));
+    }
+
+    #[test]
+    fn given_single_quoted_value_with_double_quote_borrows() {
+        let result = quoted_value(r#"'say "hi"'  "#);
+        assert_eq!(result, Ok(("  ", Cow::Borrowed(r#"say "hi""#))));
+    }
+
+    #[test]
+    fn given_escapes_unescapes() {
+        let result = quoted_value(r#""a\"b\\c\nd\te\u{41}""#);
+        let expected: Cow<str> = Cow::Owned("a\"b\\c\nd\teA".to_string());
+        assert_eq!(result, Ok(("", expected)));
+    }
+
+    #[test]
+    fn given_unknown_escape_fails_at_backslash() {
+        let result = quoted_value(r#""abc\qdef""#);
+        assert_eq!(result, Err(Err::Failure((r#"\qdef""#, ErrorKind::Escaped))));
+    }
+
+    #[test]
+    fn given_bad_unicode_escape_fails() {
+        let result = quoted_value(r#""\u{110000}""#);
+        assert_eq!(
+            result,
+            Err(Err::Failure((r#"\u{110000}""#, ErrorKind::Escaped)))
+        );
+    }
+
+    #[test]
+    fn given_missing_closing_quote_fails_at_opening_quote() {
+        let result = quoted_value(r#""abc"#);
+        assert_eq!(result, Err(Err::Failure((r#""abc"#, ErrorKind::Eof))));
+    }
+
+    #[test]
+    fn given_unquoted_input_errors() {
+        let result = quoted_value("abc");
+        assert_eq!(result, Err(Err::Error(("abc", ErrorKind::Char))));
+    }
+}
@@ -0,0 +1,537 @@
+use super::*;
+
+fn location_of(error: &Error) -> (usize, usize) {
+    let location = error.location().unwrap();
+    (location.line, location.column)
+}
+
+#[test]
+fn given_valid_cfg_can_parse() {
+    let input =
+        "\n# comment\n[test]\nthis = is\n# another comment\nthe = way\n\n[test2]\nfoo = bar";
+    let result = parse_lines(input, &ParserOptions::default()).unwrap();
+    let mut section1 = Section::new("test");
+    section1.insert("this", "is");
+    section1.insert("the", "way");
+    let mut section2 = Section::new("test2");
+    section2.insert("foo", "bar");
+    assert_eq!(result, vec![section1, section2]);
+}
+
+#[test]
+fn given_crlf_line_endings_can_parse() {
+    let result = parse_lines("[test]\r\nthis = is\r\n", &ParserOptions::default()).unwrap();
+    let mut expected = Section::new("test");
+    expected.insert("this", "is");
+    assert_eq!(result, vec![expected]);
+}
+
+#[test]
+fn given_quoted_values_can_parse() {
+    let input = "[test]\ndescription = \"Cent OS 7\"  \npath = '/opt/my tools/bin'\nescaped = \"tab\\there\"\n";
+    let result = parse_lines(input, &ParserOptions::default()).unwrap();
+    let mut expected = Section::new("test");
+    expected.insert("description", "Cent OS 7");
+    expected.insert("path", "/opt/my tools/bin");
+    expected.insert("escaped", "tab\there".to_string());
+    assert_eq!(result, vec![expected]);
+}
+
+#[test]
+fn given_spaces_in_values_option_can_parse_unquoted_spaces() {
+    let input = "[os]\nlabel = Linux CentOS 7 (64 bit)   \nquoted = \" padded \"\nempty =\n";
+    let opts = ParserOptions::new().spaces_in_values(true);
+    let result = parse_lines(input, &opts).unwrap();
+    let mut expected = Section::new("os");
+    expected.insert("label", "Linux CentOS 7 (64 bit)");
+    expected.insert("quoted", " padded ");
+    expected.insert("empty", "");
+    assert_eq!(result, vec![expected]);
+}
+
+#[test]
+fn given_comment_prefixes_can_parse_semicolon_comments() {
+    let input = "; generated\n[os]\n  ; explanation\nbits = 64\n";
+    let opts = ParserOptions::new().comment_prefixes(vec![";"]);
+    let result = parse_lines(input, &opts).unwrap();
+    let mut expected = Section::new("os");
+    expected.insert("bits", "64");
+    assert_eq!(result, vec![expected]);
+}
+
+#[test]
+fn given_inline_comment_prefixes_can_parse_inline_comments() {
+    let input = "[os] # the os\nbits = 64   # or 32\nname = \"cent # 7\" ; quoted\nurl = a#b\n";
+    let opts = ParserOptions::new().inline_comment_prefixes(vec!["#", ";"]);
+    let result = parse_lines(input, &opts);
+    // '#' is not a legal character in an unquoted strict value
+    assert!(matches!(
+        result,
+        Err(Error::IllegalCharacter { character: '#', .. })
+    ));
+
+    let opts = opts.spaces_in_values(true);
+    let result = parse_lines(input, &opts).unwrap();
+    let mut expected = Section::new("os");
+    expected.insert("bits", "64");
+    expected.insert("name", "cent # 7");
+    expected.insert("url", "a#b");
+    assert_eq!(result, vec![expected]);
+}
+
+#[test]
+fn given_no_inline_comment_prefixes_comment_is_illegal() {
+    let result = parse_lines("[os]\nbits = 64 # or 32\n", &ParserOptions::default());
+    assert!(matches!(
+        result,
+        Err(Error::IllegalCharacter { character: ' ', .. })
+    ));
+}
+
+mod continuation {
+    use super::*;
+
+    #[test]
+    fn given_indented_lines_continues_value() {
+        let input = "[site]\nhosts = lichost1\n    lichost2\n# skipped\n    lichost3\n\nbits = 64\nname = x\n";
+        let opts = ParserOptions::new().continuation(Continuation::Indented);
+        let result = parse_lines(input, &opts).unwrap();
+        let mut expected = Section::new("site");
+        expected.insert("hosts", "lichost1\nlichost2\nlichost3".to_string());
+        expected.insert("bits", "64");
+        expected.insert("name", "x");
+        assert_eq!(result, vec![expected]);
+    }
+
+    #[test]
+    fn given_blank_lines_between_indented_lines_keeps_them() {
+        let input = "[site]\nx = 1\n  2\n\n  3\n\n\ny = 4\n";
+        let opts = ParserOptions::new().continuation(Continuation::Indented);
+        let result = parse_lines(input, &opts).unwrap();
+        let mut expected = Section::new("site");
+        expected.insert("x", "1\n2\n\n3".to_string());
+        expected.insert("y", "4");
+        assert_eq!(result, vec![expected]);
+    }
+
+    #[test]
+    fn given_blank_line_then_less_indented_key_ends_value() {
+        let input = "[site]\n  x = 1\n    2\n\n  y = 3\n";
+        let opts = ParserOptions::new().continuation(Continuation::Indented);
+        let result = parse_lines(input, &opts).unwrap();
+        let mut expected = Section::new("site");
+        expected.insert("x", "1\n2".to_string());
+        expected.insert("y", "3");
+        assert_eq!(result, vec![expected]);
+    }
+
+    #[test]
+    fn given_indented_key_lines_are_not_continued_when_disabled() {
+        let input = "[site]\n  hosts = lichost1\n    bits = 64\n";
+        let result = parse_lines(input, &ParserOptions::default()).unwrap();
+        let mut expected = Section::new("site");
+        expected.insert("hosts", "lichost1");
+        expected.insert("bits", "64");
+        assert_eq!(result, vec![expected]);
+    }
+
+    #[test]
+    fn given_backslash_continues_value() {
+        let input = "[site]\npaths = /usr/lib\\\n   /opt/lib\\\n/usr/local/lib\nquoted = \"C:\\\\\"\nbits = 64\n";
+        let opts = ParserOptions::new().continuation(Continuation::Backslash);
+        let result = parse_lines(input, &opts).unwrap();
+        let mut expected = Section::new("site");
+        expected.insert("paths", "/usr/lib\n/opt/lib\n/usr/local/lib".to_string());
+        expected.insert("quoted", "C:\\".to_string());
+        expected.insert("bits", "64");
+        assert_eq!(result, vec![expected]);
+    }
+}
+
+mod options {
+    use super::*;
+
+    #[test]
+    fn given_colon_delimiter_can_parse() {
+        let opts = ParserOptions::new().delimiters(vec!["=", ":"]);
+        let result = parse_lines("[os]\nbits: 64\nhost = a:b\n", &opts).unwrap();
+        let mut expected = Section::new("os");
+        expected.insert("bits", "64");
+        expected.insert("host", "a:b");
+        assert_eq!(result, vec![expected]);
+    }
+
+    #[test]
+    fn given_empty_sections_disallowed_reports_last_empty_section() {
+        let opts = ParserOptions::new().empty_sections(false);
+        let error = parse_lines("[os]\nbits = 64\n[last]\n", &opts).unwrap_err();
+        assert!(matches!(error, Error::EmptySection { ref name, .. } if name == "last"));
+    }
+
+    #[test]
+    fn given_allow_no_value_can_parse_bare_keys() {
+        let input = "[flags]\nverbose\ndebug = 1\n";
+        let result = parse_lines(input, &ParserOptions::default());
+        assert!(matches!(result, Err(Error::MissingDelimiter(_))));
+
+        let opts = ParserOptions::new().allow_no_value(true);
+        let result = parse_lines(input, &opts).unwrap();
+        let mut expected = Section::new("flags");
+        expected.insert("verbose", "");
+        expected.insert("debug", "1");
+        assert_eq!(result, vec![expected]);
+    }
+
+    #[test]
+    fn given_fold_case_lower_cases_keys() {
+        let opts = ParserOptions::new().fold_case(true);
+        let result = parse_lines("[OS]\nBits = 64\nname = x\n", &opts).unwrap();
+        let mut expected = Section::new("OS");
+        expected.insert("bits", "64");
+        expected.insert("name", "x");
+        assert_eq!(result, vec![expected]);
+    }
+
+    #[test]
+    fn given_global_section_collects_keys_before_first_header() {
+        let input = "# site wide\nversion = 3\nsite = playa\n\n[os]\nbits = 64\n";
+        let opts = ParserOptions::new().global_section("root");
+        let result = parse_lines(input, &opts).unwrap();
+        let mut globals = Section::new("root");
+        globals.insert("version", "3");
+        globals.insert("site", "playa");
+        let mut os = Section::new("os");
+        os.insert("bits", "64");
+        assert_eq!(result, vec![globals, os]);
+    }
+
+    #[test]
+    fn given_global_section_and_matching_header_reports_duplicate() {
+        let opts = ParserOptions::new().global_section("root");
+        let error = parse_lines("version = 3\n[root]\na = b\n", &opts).unwrap_err();
+        assert!(matches!(error, Error::DuplicateSection { ref name, .. } if name == "root"));
+    }
+
+    #[test]
+    fn given_duplicate_keys_policies() {
+        let input = "[os]\nbits = 32\nBits = 64\n";
+        let parse = |policy| {
+            let opts = ParserOptions::new().fold_case(true).duplicate_keys(policy);
+            parse_lines(input, &opts)
+        };
+        let mut first = Section::new("os");
+        first.insert("bits", "32");
+        let mut last = Section::new("os");
+        last.insert("bits", "64");
+        assert_eq!(parse(DuplicatePolicy::KeepFirst).unwrap(), vec![first]);
+        assert_eq!(parse(DuplicatePolicy::KeepLast).unwrap(), vec![last]);
+        let error = parse(DuplicatePolicy::Error).unwrap_err();
+        assert!(matches!(error, Error::DuplicateKey { ref key, .. } if key == "bits"));
+        assert_eq!(location_of(&error), (3, 1));
+    }
+
+    #[test]
+    fn given_duplicate_sections_policies() {
+        let input = "[os]\na = 1\nb = 2\n[other]\nc = 3\n[os]\nb = 4\n";
+        let parse = |policy| {
+            let opts = ParserOptions::new().duplicate_sections(policy);
+            parse_lines(input, &opts).unwrap().remove(0)
+        };
+        let mut first = Section::new("os");
+        first.insert("a", "1");
+        first.insert("b", "2");
+        let mut last = Section::new("os");
+        last.insert("b", "4");
+        let mut merged = Section::new("os");
+        merged.insert("a", "1");
+        merged.insert("b", "4");
+        assert_eq!(parse(DuplicatePolicy::KeepFirst), first);
+        assert_eq!(parse(DuplicatePolicy::KeepLast), last);
+        assert_eq!(parse(DuplicatePolicy::Merge), merged);
+    }
+
+    #[test]
+    fn given_tombstones_records_removed_keys_and_sections() {
+        let input = "[os]
+a = 1
+! a
+!b  # gone
+[! old]
+";
+        let opts = ParserOptions::new()
+            .tombstones(true)
+            .inline_comment_prefixes(vec!["#"]);
+        let result = parse_lines(input, &opts).unwrap();
+        assert_eq!(result[0].removed_keys(), ["a", "b"]);
+        assert_eq!(result[0].origin("a"), None);
+        assert!(!result[0].replaces());
+        assert_eq!(result[1].name(), "old");
+        assert!(result[1].replaces());
+    }
+
+    #[test]
+    fn given_tombstones_disabled_reports_invalid_key_and_header() {
+        let error = parse_lines(
+            "[os]
+!a
+",
+            &ParserOptions::new(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::InvalidKey(_)));
+        let error = parse_lines(
+            "[!os]
+",
+            &ParserOptions::new(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::InvalidHeader(_)));
+        let opts = ParserOptions::new().tombstones(true);
+        let error = parse_lines(
+            "[os]
+!a = 1
+",
+            &opts,
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::InvalidKey(_)));
+    }
+}
+
+#[test]
+fn given_unterminated_quote_reports_unterminated_quote() {
+    let error = parse_lines("[test]\nkey = \"Cent OS 7\n", &ParserOptions::default()).unwrap_err();
+    assert!(matches!(error, Error::UnterminatedQuote(_)));
+    assert_eq!(location_of(&error), (2, 7));
+}
+
+#[test]
+fn given_bad_escape_reports_invalid_escape() {
+    let error = parse_lines("[test]\nkey = \"a\\qb\"\n", &ParserOptions::default()).unwrap_err();
+    assert!(matches!(error, Error::InvalidEscape(_)));
+    assert_eq!(location_of(&error), (2, 9));
+}
+
+#[test]
+fn given_text_after_closing_quote_reports_illegal_character() {
+    let error = parse_lines("[test]\nkey = \"a\" b\n", &ParserOptions::default()).unwrap_err();
+    assert!(matches!(error, Error::IllegalCharacter { .. }));
+}
+
+#[test]
+fn given_bad_header_reports_invalid_header() {
+    let error = parse_lines(
+        "[test]\na = b\n[bad header]\nc = d\n",
+        &ParserOptions::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(error, Error::InvalidHeader(_)));
+    assert_eq!(location_of(&error), (3, 6));
+    assert_eq!(error.location().unwrap().source_line, "[bad header]");
+}
+
+#[test]
+fn given_bad_key_reports_invalid_key() {
+    let error = parse_lines("[test]\n  bad-key = b\n", &ParserOptions::default()).unwrap_err();
+    assert!(matches!(error, Error::InvalidKey(_)));
+    assert_eq!(location_of(&error), (2, 6));
+}
+
+#[test]
+fn given_missing_equals_reports_missing_delimiter() {
+    let error = parse_lines("[test]\nkey value\n", &ParserOptions::default()).unwrap_err();
+    assert!(matches!(error, Error::MissingDelimiter(_)));
+    assert_eq!(location_of(&error), (2, 5));
+}
+
+#[test]
+fn given_space_in_value_reports_illegal_character() {
+    let error = parse_lines("[test]\nkey = two words\n", &ParserOptions::default()).unwrap_err();
+    match error {
+        Error::IllegalCharacter {
+            character,
+            location,
+        } => {
+            assert_eq!(character, ' ');
+            assert_eq!((location.line, location.column), (2, 10));
+        }
+        _ => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn given_repeated_header_reports_duplicate_section() {
+    let error =
+        parse_lines("[test]\na = b\n[test]\nc = d\n", &ParserOptions::default()).unwrap_err();
+    assert!(matches!(error, Error::DuplicateSection { ref name, .. } if name == "test"));
+    assert_eq!(location_of(&error), (3, 1));
+}
+
+#[test]
+fn given_key_before_header_reports_missing_header() {
+    let error = parse_lines("a = b\n[test]\nc = d\n", &ParserOptions::default()).unwrap_err();
+    assert!(matches!(error, Error::MissingHeader(_)));
+}
+
+#[test]
+fn given_header_without_keys_reports_empty_section() {
+    let opts = ParserOptions::new().empty_sections(false);
+    let error = parse_lines("[test]\n[test2]\nc = d\n", &opts).unwrap_err();
+    assert!(matches!(error, Error::EmptySection { ref name, .. } if name == "test"));
+    assert_eq!(location_of(&error), (1, 1));
+}
+
+#[test]
+fn given_empty_input_returns_no_sections() {
+    assert_eq!(parse_lines("", &ParserOptions::default()).unwrap(), vec![]);
+    let result = parse_lines("\n# nothing here\n", &ParserOptions::default()).unwrap();
+    assert_eq!(result, vec![]);
+}
+
+#[test]
+fn given_header_without_keys_returns_empty_section() {
+    let result = parse_lines(
+        "[future_os]\n[test]\nc = d\n[last]",
+        &ParserOptions::default(),
+    );
+    let mut test = Section::new("test");
+    test.insert("c", "d");
+    assert_eq!(
+        result.unwrap(),
+        vec![Section::new("future_os"), test, Section::new("last")]
+    );
+}
+
+mod inheritance {
+    use super::*;
+
+    fn opts() -> ParserOptions {
+        ParserOptions::new().inheritance(true)
+    }
+
+    #[test]
+    fn given_parent_in_header_or_key_records_parent() {
+        let input = "[b : a]
+x = 1
+[c]
+inherits = b
+y = 2
+[a]
+";
+        let result = parse_lines(input, &opts()).unwrap();
+        assert_eq!(result[0].parent(), Some("a"));
+        assert_eq!(result[1].parent(), Some("b"));
+        let mut expected = Section::new("c");
+        expected.insert("y", "2");
+        expected.set_parent("b");
+        assert_eq!(result[1], expected);
+    }
+
+    #[test]
+    fn given_inheritance_disabled_parent_header_is_invalid() {
+        let error = parse_lines(
+            "[b : a]
+[a]
+",
+            &ParserOptions::new(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::InvalidHeader(_)));
+        let result = parse_lines(
+            "[a]
+inherits = b
+",
+            &ParserOptions::new(),
+        )
+        .unwrap();
+        assert_eq!(result[0].parent(), None);
+    }
+
+    #[test]
+    fn given_missing_parent_reports_declaring_section_only() {
+        let input = "[c : b]
+[b]
+inherits = nope
+";
+        let (_, errors) = parse_lines_recovering(input, &opts());
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            Error::MissingParent { section, parent, .. } if section == "b" && parent == "nope"
+        ));
+        assert_eq!(location_of(&errors[0]), (3, 1));
+    }
+
+    #[test]
+    fn given_cycle_reports_each_member() {
+        let input = "[a : c]
+[b : a]
+[c : b]
+[d : a]
+";
+        let (_, errors) = parse_lines_recovering(input, &opts());
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0].message(), "inheritance cycle a -> c -> b -> a");
+        assert_eq!(location_of(&errors[0]), (1, 1));
+        let error = parse_lines(
+            "[a : a]
+",
+            &opts(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::InheritanceCycle { ref chain, .. } if chain.len() == 2));
+    }
+}
+
+mod recovering {
+    use super::*;
+
+    #[test]
+    fn given_several_bad_lines_reports_each() {
+        let input = "[test]\na = b\nbad line\nc = two words\nd = e\n";
+        let (sections, errors) = parse_lines_recovering(input, &ParserOptions::default());
+        let mut expected = Section::new("test");
+        expected.insert("a", "b");
+        expected.insert("d", "e");
+        assert_eq!(sections, vec![expected]);
+        let lines: Vec<usize> = errors.iter().map(|e| location_of(e).0).collect();
+        assert_eq!(lines, vec![3, 4]);
+    }
+
+    #[test]
+    fn given_bad_header_skips_its_keys() {
+        let input = "[test]\na = b\n[bad header]\nc = d\n[test2]\ne = f\n";
+        let (sections, errors) = parse_lines_recovering(input, &ParserOptions::default());
+        let names: Vec<&str> = sections.iter().map(|s| s.name()).collect();
+        assert_eq!(names, vec!["test", "test2"]);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], Error::InvalidHeader(_)));
+    }
+
+    #[test]
+    fn given_duplicate_section_keeps_first() {
+        let input = "[test]\na = b\n[test]\na = c\n";
+        let (sections, errors) = parse_lines_recovering(input, &ParserOptions::default());
+        let mut expected = Section::new("test");
+        expected.insert("a", "b");
+        assert_eq!(sections, vec![expected]);
+        assert!(matches!(errors[0], Error::DuplicateSection { .. }));
+    }
+
+    #[test]
+    fn given_keys_before_header_reports_once() {
+        let input = "a = b\nc = d\n[test]\ne = f\n";
+        let (sections, errors) = parse_lines_recovering(input, &ParserOptions::default());
+        assert_eq!(sections.len(), 1);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], Error::MissingHeader(_)));
+    }
+
+    #[test]
+    fn given_valid_cfg_reports_nothing() {
+        let (sections, errors) =
+            parse_lines_recovering("[test]\na = b\n", &ParserOptions::default());
+        assert_eq!(sections.len(), 1);
+        assert!(errors.is_empty());
+    }
+}
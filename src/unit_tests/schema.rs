@@ -0,0 +1,150 @@
+use super::*;
+use crate::ParserOptions;
+use std::path::PathBuf;
+
+fn example(name: &str) -> PathBuf {
+    let mut cfgpath = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    cfgpath.push("example_data");
+    cfgpath.push(name);
+    cfgpath
+}
+
+fn config(contents: &str) -> ConfigOwned {
+    Config::parse_cfg_from_str(contents).unwrap().to_owned()
+}
+
+fn messages(violations: &[Violation]) -> Vec<String> {
+    violations.iter().map(Violation::to_string).collect()
+}
+
+#[test]
+fn given_example_schema_operating_systems_are_valid() {
+    let schema = Schema::from_path(example("operating_systems_schema.cfg")).unwrap();
+    let config = crate::from_path(example("operating_systems.cfg")).unwrap();
+    assert!(schema.validate(&config).is_empty());
+}
+
+#[test]
+fn given_broken_sections_validate_reports_every_violation() {
+    let schema = Schema::from_path(example("operating_systems_schema.cfg")).unwrap();
+    let config = config(
+        "[cent8_64]\n\
+         architecture = linux_rhel8_x86_64\n\
+         type = WINDOWS\n\
+         bits = 128\n\
+         python_version = 3.9\n\
+         \n\
+         [cent6_32]\n\
+         architecture = linux_cent6_x86_64\n\
+         type = LINUX\n\
+         bits = thirty-two\n\
+         status = Retired\n\
+         python_version = 1.5\n\
+         \n\
+         [rhel9_64]\n\
+         bits = 16\n",
+    );
+    assert_eq!(
+        messages(&schema.validate(&config)),
+        vec![
+            "line 10: value 'thirty-two' of key 'bits' in section 'cent6_32' is not a valid i64",
+            "line 11: value 'Retired' of key 'status' in section 'cent6_32' must be one of Current, Legacy, Deprecated",
+            "line 12: value '1.5' of key 'python_version' in section 'cent6_32' must be at least 2",
+            "line 2: value 'linux_rhel8_x86_64' of key 'architecture' in section 'cent8_64' must match the pattern '^linux_cent[0-9]+_x86_64$'",
+            "line 3: value 'WINDOWS' of key 'type' in section 'cent8_64' must be one of LINUX",
+            "line 4: value '128' of key 'bits' in section 'cent8_64' must be one of 32, 64",
+        ]
+    );
+}
+
+#[test]
+fn given_missing_required_key_validate_reports_it_without_origin() {
+    let schema = Schema::new().rule("*", Rule::new("bits").required(true));
+    let violations = schema.validate(&config("[os]\nlabel = centos\n"));
+    assert_eq!(violations.len(), 1);
+    assert!(violations[0].origin.is_none());
+    assert!(matches!(
+        &violations[0].error,
+        Error::MissingKey { section: Some(section), key } if section == "os" && key == "bits"
+    ));
+}
+
+#[test]
+fn given_inherited_value_validate_reports_its_origin() {
+    let schema = Schema::new().rule("child", Rule::new("bits").value_type(ValueType::Int));
+    let opts = ParserOptions::new().inheritance(true);
+    let contents = "[base]\nbits = many\n\n[child : base]\n";
+    let config = Config::parse_with(contents, &opts).unwrap().to_owned();
+    let violations = schema.validate(&config);
+    assert_eq!(
+        messages(&violations),
+        vec!["line 2: value 'many' of key 'bits' in section 'child' is not a valid i64"]
+    );
+}
+
+#[test]
+fn given_defaults_section_validate_checks_only_inheriting_sections() {
+    let opts = ParserOptions::new().default_section("DEFAULT");
+    let schema = Schema::new()
+        .rule("*", Rule::new("user").required(true))
+        .rule("*", Rule::new("secure").value_type(ValueType::Bool));
+    let config = Config::parse_with("[DEFAULT]\nsecure = maybe\n\n[site]\nuser = fred\n", &opts)
+        .unwrap()
+        .to_owned();
+    assert_eq!(
+        messages(&schema.validate(&config)),
+        vec!["line 2: value 'maybe' of key 'secure' in section 'site' is not a valid bool"]
+    );
+}
+
+#[test]
+fn given_defaults_apply_defaults_fills_missing_keys() {
+    let schema = Schema::parse_cfg_from_str(
+        "[status]\nkey = status\nrequired = yes\ndefault = Current\nsections = \"cent*\"\n",
+    )
+    .unwrap();
+    let mut config =
+        config("[cent7_64]\nbits = 64\n\n[cent6_64]\nstatus = Legacy\n\n[site]\nuser = fred\n");
+    assert!(schema.validate(&config).is_empty());
+
+    schema.apply_defaults(&mut config);
+    assert_eq!(config.get_value("cent7_64", "status"), Some("Current"));
+    assert_eq!(config.get_value("cent6_64", "status"), Some("Legacy"));
+    assert_eq!(config.get_value("site", "status"), None);
+}
+
+#[test]
+fn given_bad_rules_schema_fails_to_load() {
+    let result = Schema::parse_cfg_from_str("[bits]\nkey = bits\ntype = number\n");
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "invalid schema rule in section 'bits': unknown type 'number', expected one of string, int, float or bool"
+    );
+
+    let result = Schema::parse_cfg_from_str("[bits]\nkey = bits\nmaximum = 64\n");
+    assert!(matches!(
+        result,
+        Err(Error::InvalidSchema { message, .. }) if message == "unknown property 'maximum'"
+    ));
+}
+
+#[test]
+fn given_malformed_rules_validate_reports_them_first() {
+    let schema = Schema::new()
+        .rule("cent*", Rule::new("bits").pattern("(unclosed"))
+        .rule("cent[", Rule::new("bits"))
+        .rule("*", Rule::new("status").required(true));
+    let violations = schema.validate(&config("[cent7_64]\nbits = 64\n"));
+    assert_eq!(violations.len(), 3);
+    assert!(violations[..2].iter().all(|violation| matches!(
+        violation,
+        Violation {
+            error: Error::InvalidSchema { section: None, .. },
+            origin: None,
+        }
+    )));
+    assert_eq!(
+        violations[2].to_string(),
+        "missing key 'status' in section 'cent7_64'"
+    );
+}
@@ -0,0 +1,186 @@
+use super::*;
+use crate::Config;
+
+fn parsed() -> SectionOwned {
+    let config = Config::parse_cfg_from_str("[os]\nbits = 64\narch = x86_64\n").unwrap();
+    config.section("os").unwrap().to_owned().1
+}
+
+#[test]
+fn remove_given_parsed_key_forgets_its_origin() {
+    let mut section = parsed();
+    assert_eq!(section.remove("bits"), Some("64".to_string()));
+    assert_eq!(section.remove("bits"), None);
+    assert!(section.origin("bits").is_none());
+    assert!(section.origin("arch").is_some());
+}
+
+#[test]
+fn get_mut_given_key_edits_value_in_place() {
+    let mut section = parsed();
+    section.get_mut("arch").unwrap().push_str("_v2");
+    assert_eq!(
+        section.get_mut("arch").map(|value| value.as_str()),
+        Some("x86_64_v2")
+    );
+    assert!(section.get_mut("missing").is_none());
+}
+
+#[test]
+fn entry_given_missing_key_inserts_it() {
+    let mut section = parsed();
+    section
+        .entry("label".to_string())
+        .or_insert_with(|| "centos".to_string());
+    section
+        .entry("bits".to_string())
+        .or_insert_with(|| "32".to_string());
+    let mut expected = SectionOwned::new();
+    expected.insert("bits", "64");
+    expected.insert("arch", "x86_64");
+    expected.insert("label", "centos");
+    assert_eq!(section, expected);
+}
+
+#[test]
+fn get_mut_and_entry_clear_the_origin() {
+    let mut section = parsed();
+    assert!(section.origin("arch").is_some());
+    section.get_mut("arch").unwrap().push_str("_v2");
+    assert!(section.origin("arch").is_none());
+
+    assert!(section.origin("bits").is_some());
+    let entry = section.entry("bits".to_string());
+    assert_eq!(entry.key(), "bits");
+    entry.and_modify(|value| value.push('0')).or_insert("32");
+    assert_eq!(section.get("bits"), Some("640"));
+    assert!(section.origin("bits").is_none());
+
+    section.entry("label".to_string()).or_insert("centos");
+    assert_eq!(section.get("label"), Some("centos"));
+}
+
+#[test]
+fn retain_given_predicate_drops_other_keys_and_origins() {
+    let mut section = parsed();
+    section.retain(|key, value| {
+        value.make_ascii_uppercase();
+        key == "arch"
+    });
+    let mut expected = SectionOwned::new();
+    expected.insert("arch", "X86_64");
+    assert_eq!(section, expected);
+    assert!(section.origin("bits").is_none());
+    assert!(section.origin("arch").is_some());
+}
+
+#[test]
+fn clear_keeps_parent() {
+    let mut section = parsed();
+    section.set_parent("base");
+    section.clear();
+    let mut expected = SectionOwned::new();
+    expected.set_parent("base");
+    assert_eq!(section, expected);
+}
+
+#[test]
+fn get_given_parsed_section_returns_values() {
+    let config = Config::parse_cfg_from_str("[os]\nbits = 64\narch = x86_64\n").unwrap();
+    let section = config.section("os").unwrap();
+    assert_eq!(section.get("bits"), Some("64"));
+    assert_eq!(section.get("missing"), None);
+    assert!(section.contains_key("arch"));
+    assert!(!section.contains_key("missing"));
+    assert_eq!(section.len(), 2);
+    assert!(!section.is_empty());
+    assert!(Section::new("empty").is_empty());
+
+    let owned = parsed();
+    assert_eq!(owned.get("arch"), Some("x86_64"));
+    assert!(owned.contains_key("bits"));
+    assert_eq!(owned.len(), 2);
+    assert!(SectionOwned::new().is_empty());
+}
+
+#[test]
+fn iter_given_section_visits_every_pair() {
+    let config = Config::parse_cfg_from_str("[os]\nbits = 64\narch = x86_64\n").unwrap();
+    let section = config.section("os").unwrap();
+    let mut pairs = section.iter().collect::<Vec<_>>();
+    pairs.sort();
+    assert_eq!(pairs, vec![("arch", "x86_64"), ("bits", "64")]);
+    let mut keys = section.keys().collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(keys, vec!["arch", "bits"]);
+    let mut values = section.values().collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(values, vec!["64", "x86_64"]);
+
+    let owned = parsed();
+    let mut pairs = (&owned).into_iter().collect::<Vec<_>>();
+    pairs.sort();
+    assert_eq!(pairs, vec![("arch", "x86_64"), ("bits", "64")]);
+    assert_eq!(owned.keys().count(), 2);
+    assert_eq!(owned.values().count(), 2);
+    let mut pairs = owned.into_iter().collect::<Vec<_>>();
+    pairs.sort();
+    assert_eq!(pairs[0], ("arch".to_string(), "x86_64".to_string()));
+}
+
+#[test]
+fn index_given_key_returns_value() {
+    let config = Config::parse_cfg_from_str("[os]\nbits = 64\n").unwrap();
+    assert_eq!(&config.section("os").unwrap()["bits"], "64");
+    assert_eq!(&parsed()["arch"], "x86_64");
+}
+
+#[test]
+#[should_panic(expected = "section 'os' has no key 'missing'")]
+fn index_given_missing_key_panics() {
+    let config = Config::parse_cfg_from_str("[os]\nbits = 64\n").unwrap();
+    let _ = &config.section("os").unwrap()["missing"];
+}
+
+#[test]
+fn extend_given_pairs_inserts_each() {
+    let mut section = Section::new("os");
+    section.extend(vec![("bits", "64"), ("arch", "x86_64")]);
+    assert_eq!(section.get("arch"), Some("x86_64"));
+    assert_eq!(section.into_iter().count(), 2);
+
+    let mut owned = SectionOwned::new();
+    owned.extend(vec![("bits".to_string(), "64".to_string())]);
+    owned.extend(parsed());
+    assert_eq!(owned, parsed());
+}
+
+#[test]
+fn get_bool_given_owned_section_reports_no_section() {
+    let mut section = SectionOwned::new();
+    section.insert("secure", "off");
+    section.insert("bits", "64x");
+    assert_eq!(section.get_bool("secure").unwrap(), Some(false));
+    assert_eq!(section.get_bool("missing").unwrap(), None);
+    let error = section.get_int("bits").unwrap_err();
+    assert!(matches!(error, Error::InvalidValue { section: None, .. }));
+    assert_eq!(section.get_list("bits", ","), Some(vec!["64x"]));
+}
+
+#[test]
+fn get_int_given_owned_section_of_config_names_the_section() {
+    let error = parsed().get_int("arch").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "value 'x86_64' of key 'arch' in section 'os' is not a valid i64"
+    );
+
+    let mut config = crate::ConfigOwned::new();
+    config.set("site", "secure", "maybe");
+    let error = config.get_mut("site").unwrap().get_bool("secure");
+    assert!(
+        matches!(error, Err(Error::InvalidValue { section: Some(name), .. }) if name == "site")
+    );
+    assert!(config.rename_section("site", "studio"));
+    assert_eq!(config.section("studio").unwrap().name(), Some("studio"));
+}
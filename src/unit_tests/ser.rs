@@ -0,0 +1,169 @@
+use super::*;
+use crate::de::from_str;
+use crate::Config;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Status {
+    Current,
+    Legacy,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct OperatingSystem {
+    architecture: String,
+    bits: u8,
+    status: Status,
+    secure: Option<bool>,
+    hosts: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Systems {
+    cent7_64: OperatingSystem,
+    cent6_64: Option<OperatingSystem>,
+}
+
+fn systems() -> Systems {
+    Systems {
+        cent7_64: OperatingSystem {
+            architecture: "linux_cent7_x86_64".to_string(),
+            bits: 64,
+            status: Status::Current,
+            secure: Some(true),
+            hosts: vec!["lichost1".to_string(), "lichost2".to_string()],
+        },
+        cent6_64: None,
+    }
+}
+
+#[test]
+fn given_struct_of_structs_to_string_writes_fields_in_order() {
+    let text = to_string(&systems()).unwrap();
+    assert_eq!(
+        text,
+        "[cent7_64]\n\
+         architecture = linux_cent7_x86_64\n\
+         bits = 64\n\
+         status = Current\n\
+         secure = true\n\
+         hosts = lichost1,lichost2\n"
+    );
+    let config = Config::parse_cfg_from_str(&text).unwrap();
+    assert_eq!(config.get_value("cent7_64", "status"), Some("Current"));
+}
+
+#[test]
+fn given_serialized_struct_from_str_reads_it_back() {
+    let mut original = systems();
+    original.cent6_64 = Some(OperatingSystem {
+        architecture: "linux cent6".to_string(),
+        bits: 32,
+        status: Status::Legacy,
+        secure: None,
+        hosts: Vec::new(),
+    });
+    let text = to_string(&original).unwrap();
+    assert_eq!(from_str::<Systems>(&text).unwrap(), original);
+}
+
+#[test]
+fn given_map_of_maps_to_string_writes_sections() {
+    let mut os = BTreeMap::new();
+    os.insert("label", "Linux CentOS");
+    os.insert("bits", "64");
+    let mut sections = BTreeMap::new();
+    sections.insert("os", os);
+    sections.insert("empty", BTreeMap::new());
+
+    let text = to_string(&sections).unwrap();
+    assert_eq!(
+        text,
+        "[empty]\n\n[os]\nbits = 64\nlabel = \"Linux CentOS\"\n"
+    );
+    assert!(Config::parse_cfg_from_str(&text).is_ok());
+}
+
+#[test]
+fn given_section_owned_to_string_writes_its_keys() {
+    let mut section = SectionOwned::new();
+    section.insert("bits", "64");
+    let mut sections = BTreeMap::new();
+    sections.insert("os", section);
+    assert_eq!(to_string(&sections).unwrap(), "[os]\nbits = 64\n");
+}
+
+#[test]
+fn given_list_separator_to_string_with_joins_on_it() {
+    let opts = FormatOptions::new().list_separator(", ");
+    let text = to_string_with(&systems(), &opts).unwrap();
+    assert!(text.contains("hosts = \"lichost1, lichost2\"\n"));
+    assert_eq!(from_str::<Systems>(&text).unwrap(), systems());
+}
+
+#[test]
+fn given_item_holding_separator_to_string_fails() {
+    let mut original = systems();
+    original.cent7_64.hosts = vec!["a,b".to_string()];
+    let error = to_string(&original).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "list item 'a,b' contains the separator ',' for key 'hosts' in section 'cent7_64'"
+    );
+}
+
+#[test]
+fn given_nesting_too_deep_to_string_fails() {
+    #[derive(Serialize)]
+    struct Deep {
+        os: BTreeMap<&'static str, BTreeMap<&'static str, u8>>,
+    }
+    let mut inner = BTreeMap::new();
+    inner.insert("bits", 64);
+    let mut os = BTreeMap::new();
+    os.insert("arch", inner);
+
+    let error = to_string(&Deep { os }).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "a map is nested too deeply to be written as a value for key 'arch' in section 'os'"
+    );
+
+    let nested = vec![("os", vec![("hosts", vec![vec![1, 2]])])];
+    let nested = nested
+        .into_iter()
+        .map(|(name, keys)| (name, keys.into_iter().collect::<BTreeMap<_, _>>()))
+        .collect::<BTreeMap<_, _>>();
+    assert!(matches!(
+        to_string(&nested),
+        Err(Error::Serde { key: Some(key), .. }) if key == "hosts"
+    ));
+}
+
+#[test]
+fn given_value_outside_section_to_string_fails() {
+    let mut sections = BTreeMap::new();
+    sections.insert("user", "fred");
+    let error = to_string(&sections).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "a section must be serialized from a struct or map of keys, not a string in section 'user'"
+    );
+    assert!(matches!(
+        to_string(&64),
+        Err(Error::Serde { section: None, .. })
+    ));
+}
+
+#[test]
+fn given_invalid_key_to_string_fails() {
+    let mut os = BTreeMap::new();
+    os.insert("bad key", "1");
+    let mut sections = BTreeMap::new();
+    sections.insert("os", os);
+    assert!(matches!(
+        to_string(&sections),
+        Err(Error::InvalidName(name)) if name == "bad key"
+    ));
+}
@@ -0,0 +1,75 @@
+use super::*;
+
+#[test]
+fn to_bool_given_accepted_words_ignores_case() {
+    for word in ["yes", "On", "TRUE", "1"].iter() {
+        assert!(to_bool(None, "k", word).unwrap());
+    }
+    for word in ["no", "Off", "FALSE", "0"].iter() {
+        assert!(!to_bool(None, "k", word).unwrap());
+    }
+}
+
+#[test]
+fn to_bool_given_other_word_names_key_and_value() {
+    let error = to_bool(Some("os"), "secure", "maybe").unwrap_err();
+    assert!(matches!(
+        &error,
+        Error::InvalidValue { section: Some(section), key, value, target }
+            if section == "os" && key == "secure" && value == "maybe" && target == "bool"
+    ));
+}
+
+#[test]
+fn convert_given_bad_number_names_target_type() {
+    let error = convert::<i64>(None, "bits", "sixty four").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "value 'sixty four' of key 'bits' is not a valid i64"
+    );
+    assert_eq!(convert::<f64>(None, "version", "2.7").unwrap(), 2.7);
+}
+
+#[test]
+fn convert_given_bad_value_names_short_type() {
+    let error = convert::<std::net::Ipv4Addr>(Some("site"), "proxy", "x").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "value 'x' of key 'proxy' in section 'site' is not a valid Ipv4Addr"
+    );
+    assert_eq!(short_type_name::<String>(), "String");
+    assert_eq!(
+        short_type_name::<Vec<Option<std::path::PathBuf>>>(),
+        "Vec<Option<PathBuf>>"
+    );
+    assert_eq!(
+        short_type_name::<(u8, std::collections::HashMap<String, i64>)>(),
+        "(u8, HashMap<String, i64>)"
+    );
+}
+
+#[test]
+fn split_list_given_padding_and_empty_items_skips_them() {
+    assert_eq!(
+        split_list(" lichost1 ,lichost2,, ", ","),
+        vec!["lichost1", "lichost2"]
+    );
+    assert!(split_list("", ",").is_empty());
+}
+
+#[test]
+fn from_value_given_list_converts_each_item() {
+    let bits = Vec::<u8>::from_value(None, "bits", "32, 64").unwrap();
+    assert_eq!(bits, vec![32, 64]);
+    let error = Vec::<u8>::from_value(Some("os"), "bits", "32,sixty").unwrap_err();
+    assert!(matches!(error, Error::InvalidValue { value, .. } if value == "sixty"));
+}
+
+#[test]
+fn from_value_given_bool_accepts_words() {
+    assert!(bool::from_value(None, "secure", "on").unwrap());
+    assert_eq!(
+        PathBuf::from_value(None, "root", "/opt").unwrap(),
+        PathBuf::from("/opt")
+    );
+}
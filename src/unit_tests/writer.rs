@@ -0,0 +1,132 @@
+use super::*;
+use crate::{from_path, Continuation, ParserOptions};
+
+fn written(config: &Config<'_>, opts: &FormatOptions) -> String {
+    let mut out = Vec::new();
+    config.write_with(&mut out, opts).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn given_awkward_values_output_reparses_equal() {
+    let mut section = Section::new("os");
+    section.insert("label", "Linux CentOS 7 (64 bit)");
+    section.insert("empty", "");
+    section.insert("quotes", "say \"hi\" it's");
+    section.insert("lines", "lichost1\nlichost2");
+    section.insert("path", r"C:\tools");
+    section.insert("bell", "\u{7}ding");
+    section.insert("smile", "\u{263a}");
+    section.insert("comment", "a#b");
+    let mut config = Config::new();
+    config.insert("os", section);
+
+    let text = config.to_string();
+    assert_eq!(Config::parse_cfg_from_str(&text).unwrap(), config);
+    assert!(text.contains("smile = \u{263a}\n"));
+    assert!(text.contains("empty = \"\"\n"));
+}
+
+#[test]
+fn given_backslash_continuation_trailing_backslash_reparses_equal() {
+    let opts = ParserOptions::new().continuation(Continuation::Backslash);
+    let mut section = Section::new("paths");
+    section.insert("dir", r"C:\tools\");
+    section.insert("name", "tools");
+    let mut config = Config::new();
+    config.insert("paths", section);
+
+    let text = written(&config, &FormatOptions::new().sort_keys(true));
+    assert_eq!(text, "[paths]\ndir = \"C:\\\\tools\\\\\"\nname = tools\n");
+    assert_eq!(Config::parse_with(&text, &opts).unwrap(), config);
+}
+
+#[test]
+fn given_sorting_and_blank_lines_formats_accordingly() {
+    let config = Config::parse_cfg_from_str("[b]\nz = 1\ny = 2\n[a]\nx = 3\n").unwrap();
+    let opts = FormatOptions::new()
+        .sort_sections(true)
+        .sort_keys(true)
+        .blank_lines(2)
+        .delimiter(": ");
+    assert_eq!(written(&config, &opts), "[a]\nx: 3\n\n\n[b]\ny: 2\nz: 1\n");
+}
+
+#[test]
+fn given_globals_writes_them_first_without_header() {
+    let opts = ParserOptions::new().global_section("root");
+    let config = Config::parse_with("version = 3\n[a]\nx = 1\n[b]\ny = 2\n", &opts).unwrap();
+    let text = written(&config, &FormatOptions::new().sort_sections(true));
+    assert_eq!(text, "version = 3\n\n[a]\nx = 1\n\n[b]\ny = 2\n");
+    assert_eq!(Config::parse_with(&text, &opts).unwrap(), config);
+}
+
+#[test]
+fn given_parents_and_tombstones_output_reparses_equal() {
+    let opts = ParserOptions::new().inheritance(true).tombstones(true);
+    let contents = "[base]\nbits = 64\n!legacy\n\n[child : base]\nversion = 7\n\n[!gone]\n";
+    let config = Config::parse_with(contents, &opts).unwrap();
+    let text = written(&config, &FormatOptions::new().sort_sections(true));
+    assert_eq!(text, contents);
+    // the output needs the options which produced it
+    assert!(Config::parse_cfg_from_str(&text).is_err());
+    assert!(Config::parse_with(&text, &ParserOptions::new().inheritance(true)).is_err());
+    let owned = config.to_owned();
+    assert_eq!(Config::parse_with(&text, &opts).unwrap().to_owned(), owned);
+    let mut out = Vec::new();
+    owned
+        .write_with(&mut out, &FormatOptions::new().sort_sections(true))
+        .unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), text);
+}
+
+#[test]
+fn given_invalid_key_write_fails() {
+    let mut section = Section::new("os");
+    section.insert("bad key", "x");
+    let mut out = Vec::new();
+    let error = section.write_to(&mut out).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(error.to_string(), "'bad key' is not a valid key");
+    assert!(out.is_empty());
+}
+
+#[test]
+fn given_permissive_names_output_reparses_equal() {
+    let parse_opts = ParserOptions::dialect(crate::Dialect::Python);
+    let config = Config::parse_with("[My App]\nlog-level = 2\n", &parse_opts).unwrap();
+    let mut out = Vec::new();
+    assert!(config.write_to(&mut out).is_err());
+
+    let opts = FormatOptions::new().names(crate::Names::Permissive);
+    let text = written(&config, &opts);
+    assert_eq!(text, "[My App]\nlog-level = 2\n");
+    assert_eq!(Config::parse_with(&text, &parse_opts).unwrap(), config);
+
+    let mut section = Section::new("os");
+    section.insert("a = b", "x");
+    let error = section.write_with(&mut out, &opts).unwrap_err();
+    assert_eq!(error.to_string(), "'a = b' is not a valid key");
+}
+
+#[test]
+fn given_section_owned_writes_keys_without_header() {
+    let mut section = SectionOwned::new();
+    section.insert("bits", "64");
+    assert_eq!(section.to_string(), "bits = 64\n");
+}
+
+#[test]
+fn given_example_data_output_reparses_equal() {
+    let mut cfgpath = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    cfgpath.push("example_data");
+    cfgpath.push("operating_systems.cfg");
+    let config = from_path(cfgpath).unwrap();
+    let mut out = Vec::new();
+    config.write_to(&mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(
+        Config::parse_cfg_from_str(&text).unwrap().to_owned(),
+        config
+    );
+}
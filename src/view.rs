@@ -0,0 +1,170 @@
+//! view
+//!
+//! This module houses SectionView, which presents the keys of a section
+//! along with any it inherits from elsewhere in the config: from its
+//! parent, its parent's parent and so on, and finally from a `[DEFAULT]`
+//! section. Lookups try the section itself first, and fall back to the
+//! sections it inherits from in turn.
+use crate::parser::driver::fold_key;
+
+// Read access to the key value pairs of a section, regardless of whether
+// the section is borrowed or owned.
+pub(crate) trait Items {
+    // retrieve the stored key and value matching the supplied key
+    fn item(&self, key: &str) -> Option<(&str, &str)>;
+    // iterate over the key value pairs
+    fn items(&self) -> Box<dyn Iterator<Item = (&str, &str)> + '_>;
+    // the name of the section inherited from, if any
+    fn parent(&self) -> Option<&str>;
+    // the keys removed via tombstones
+    fn removed(&self) -> Vec<&str>;
+    // was the section declared with a tombstone header
+    fn replaces(&self) -> bool;
+}
+
+/// A key value pair retrieved through a SectionView
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ViewItem<'c> {
+    /// The key
+    pub key: &'c str,
+    /// The value of the key
+    pub value: &'c str,
+    /// The name of the section the key was defined in
+    pub section: &'c str,
+    /// True when the key was not defined in the viewed section itself
+    pub inherited: bool,
+}
+
+/// A section viewed along with the keys it inherits. Retrieved via
+/// Config::get or ConfigOwned::get.
+///
+/// # Example
+///
+/// ```
+/// use cfgparser::{Config, Dialect, ParserOptions};
+///
+/// let contents = "[DEFAULT]\nbits = 64\nstatus = Current\n\n[cent6_64]\nstatus = Legacy\n";
+/// let opts = ParserOptions::dialect(Dialect::Python);
+/// let config = Config::parse_with(contents, &opts).unwrap();
+///
+/// let view = config.get("cent6_64").unwrap();
+/// assert_eq!(view.get("bits"), Some("64"));
+/// assert_eq!(view.get("status"), Some("Legacy"));
+///
+/// let inherited = view.iter().filter(|item| item.inherited).count();
+/// assert_eq!(inherited, 1);
+/// ```
+pub struct SectionView<'c> {
+    // the viewed section, followed by the sections it inherits from, in the
+    // order in which they are searched
+    chain: Vec<(&'c str, &'c dyn Items)>,
+    // fold keys to lower case before looking them up, as the config was
+    // parsed with ParserOptions::fold_case
+    fold_case: bool,
+}
+
+impl<'c> SectionView<'c> {
+    // Create a view of the named section, given a function to look up
+    // sections by name, the name of the defaults section, if any, and
+    // whether keys are folded to lower case. The chain of parents is
+    // followed until it ends, reaches a missing section, or loops back on
+    // itself.
+    pub(crate) fn resolve<F>(
+        section: &str,
+        lookup: F,
+        defaults: Option<&str>,
+        fold_case: bool,
+    ) -> Option<Self>
+    where
+        F: Fn(&str) -> Option<(&'c str, &'c dyn Items)>,
+    {
+        let mut chain = vec![lookup(section)?];
+        while let Some(parent) = chain.last().and_then(|&(_, items)| items.parent()) {
+            match lookup(parent) {
+                Some(link) if !chain.iter().any(|(name, _)| *name == link.0) => chain.push(link),
+                _ => break,
+            }
+        }
+        if let Some(link) = defaults.and_then(&lookup) {
+            if !chain.iter().any(|(name, _)| *name == link.0) {
+                chain.push(link);
+            }
+        }
+        Some(Self { chain, fold_case })
+    }
+
+    /// Iterate over the names of the viewed section and the sections it
+    /// inherits from, in the order in which they are searched
+    pub fn chain(&self) -> impl Iterator<Item = &'c str> + '_ {
+        self.chain.iter().map(|(name, _)| *name)
+    }
+
+    /// Retrieve the name of the viewed section
+    pub fn name(&self) -> &'c str {
+        self.chain[0].0
+    }
+
+    /// Retrieve the value of a key, falling back to the inherited sections
+    /// when the viewed section does not define it. When the config was parsed
+    /// with ParserOptions::fold_case, the key is folded to lower case first.
+    pub fn get(&self, key: &str) -> Option<&'c str> {
+        self.item(key).map(|item| item.value)
+    }
+
+    /// Retrieve a key value pair, noting where it was defined
+    pub fn item(&self, key: &str) -> Option<ViewItem<'c>> {
+        let key = fold_key(key, self.fold_case);
+        let key = key.as_ref();
+        self.chain
+            .iter()
+            .enumerate()
+            .find_map(|(depth, &(section, items))| {
+                items.item(key).map(|(key, value)| ViewItem {
+                    key,
+                    value,
+                    section,
+                    inherited: depth > 0,
+                })
+            })
+    }
+
+    /// Does the viewed section define or inherit the supplied key
+    pub fn contains_key(&self, key: &str) -> bool {
+        let key = fold_key(key, self.fold_case);
+        let key = key.as_ref();
+        self.chain
+            .iter()
+            .any(|(_, items)| items.item(key).is_some())
+    }
+
+    /// Iterate over every key visible from the viewed section. Local keys come
+    /// first, followed by inherited keys which have not been overridden.
+    pub fn iter(&self) -> impl Iterator<Item = ViewItem<'c>> + '_ {
+        self.chain
+            .iter()
+            .enumerate()
+            .flat_map(move |(depth, &(section, items))| {
+                items
+                    .items()
+                    .filter(move |(key, _)| {
+                        !self.chain[..depth]
+                            .iter()
+                            .any(|(_, nearer)| nearer.item(key).is_some())
+                    })
+                    .map(move |(key, value)| ViewItem {
+                        key,
+                        value,
+                        section,
+                        inherited: depth > 0,
+                    })
+            })
+    }
+}
+
+impl std::fmt::Debug for SectionView<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|item| (item.key, item.value)))
+            .finish()
+    }
+}
@@ -0,0 +1,424 @@
+//! writer
+//!
+//! This module houses FormatOptions, which controls how configs and
+//! sections are written back out as cfg text, along with the Display and
+//! `write_to` implementations for Config, Section, ConfigOwned and
+//! SectionOwned.
+//!
+//! Values which the strict grammar could not read back unquoted are
+//! wrapped in double quotes and escaped, so that the output re-parses to an
+//! equal config. Keys of the implicit global section are written first,
+//! without a header. Section names and keys which the grammar would reject
+//! cause `write_to` to fail, whereas Display writes them as they are.
+use crate::parser::atoms::alphaword_many0_underscore_word;
+use crate::parser::ILLEGAL_CHARS;
+use crate::view::Items;
+use crate::{Config, ConfigOwned, Names, Section, SectionOwned};
+use std::fmt::{self, Write};
+use std::io;
+
+/// Controls when values are wrapped in quotes
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Quoting {
+    /// Quote only values which could not otherwise be read back, such as
+    /// those holding spaces, quotes, backslashes or newlines, or empty values
+    WhenNeeded,
+    /// Quote every value
+    Always,
+}
+
+/// Options controlling how a config is written.
+///
+/// A section with a parent is written with an inheriting header, such as
+/// `[child : parent]`, a removed key as `!key`, and a section replacing an
+/// earlier one as `[!name]`. Reading such output back requires
+/// `ParserOptions::inheritance` and `ParserOptions::tombstones` respectively,
+/// as the default grammar rejects these lines.
+///
+/// # Example
+///
+/// ```
+/// use cfgparser::{Config, FormatOptions, Quoting};
+///
+/// let config = Config::parse_cfg_from_str("[os]\nbits = 64\narch = x86_64\n").unwrap();
+/// let opts = FormatOptions::new()
+///     .delimiter("=")
+///     .sort_keys(true)
+///     .quoting(Quoting::Always);
+///
+/// let mut out = Vec::new();
+/// config.write_with(&mut out, &opts).unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), "[os]\narch=\"x86_64\"\nbits=\"64\"\n");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FormatOptions {
+    delimiter: String,
+    blank_lines: usize,
+    sort_sections: bool,
+    sort_keys: bool,
+    quoting: Quoting,
+    pub(crate) names: Names,
+    #[cfg(feature = "serde")]
+    list_separator: String,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: " = ".to_string(),
+            blank_lines: 1,
+            sort_sections: false,
+            sort_keys: false,
+            quoting: Quoting::WhenNeeded,
+            names: Names::Strict,
+            #[cfg(feature = "serde")]
+            list_separator: ",".to_string(),
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Create a new instance of FormatOptions, writing `key = value` pairs
+    /// with a blank line between sections
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the text written between a key and its value, including any
+    /// surrounding spaces. Defaults to `" = "`.
+    pub fn delimiter<S>(mut self, delimiter: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.delimiter = delimiter.into();
+        self
+    }
+
+    /// Set the number of blank lines written between sections. Defaults to 1.
+    pub fn blank_lines(mut self, count: usize) -> Self {
+        self.blank_lines = count;
+        self
+    }
+
+    /// When true, sections are written in order of their names. Otherwise they
+    /// are written in the order in which the config holds them, which is only
+    /// predictable with the `ordered` feature. Defaults to false.
+    pub fn sort_sections(mut self, sort: bool) -> Self {
+        self.sort_sections = sort;
+        self
+    }
+
+    /// When true, keys are written in sorted order. Otherwise they are written
+    /// in the order in which the section holds them, which is only
+    /// predictable with the `ordered` feature. Defaults to false.
+    pub fn sort_keys(mut self, sort: bool) -> Self {
+        self.sort_keys = sort;
+        self
+    }
+
+    /// Set when values are wrapped in quotes. Defaults to Quoting::WhenNeeded.
+    pub fn quoting(mut self, quoting: Quoting) -> Self {
+        self.quoting = quoting;
+        self
+    }
+
+    /// Set which section names and keys may be written. Names which a parser
+    /// using the same Names could not read back cause `write_with` to fail.
+    /// Defaults to Names::Strict.
+    pub fn names(mut self, names: Names) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// Set the text written between the items of a sequence, such as a Vec,
+    /// when serializing with the `serde` feature. Defaults to `","`, matching
+    /// ParserOptions::list_separator, which the deserializer splits on.
+    #[cfg(feature = "serde")]
+    pub fn list_separator<S>(mut self, separator: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.list_separator = separator.into();
+        self
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn separator(&self) -> &str {
+        &self.list_separator
+    }
+}
+
+impl fmt::Display for Config<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_config(f, &config_sections(self), &FormatOptions::default())
+    }
+}
+
+impl fmt::Display for ConfigOwned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_config(f, &owned_sections(self), &FormatOptions::default())
+    }
+}
+
+impl fmt::Display for Section<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_section(f, Some(self.name()), self, &FormatOptions::default())
+    }
+}
+
+impl fmt::Display for SectionOwned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_section(f, None, self, &FormatOptions::default())
+    }
+}
+
+impl Config<'_> {
+    /// Write the config as cfg text, using the default FormatOptions
+    pub fn write_to<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+    {
+        self.write_with(writer, &FormatOptions::default())
+    }
+
+    /// Write the config as cfg text, using the supplied FormatOptions. Fails
+    /// with `io::ErrorKind::InvalidInput` if a section name or key could not
+    /// be read back.
+    pub fn write_with<W>(&self, writer: &mut W, opts: &FormatOptions) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+    {
+        let sections = config_sections(self);
+        for (name, section) in sections.iter() {
+            validate(*name, *section, opts.names)?;
+        }
+        let mut out = String::new();
+        write_config(&mut out, &sections, opts).map_err(format_error)?;
+        writer.write_all(out.as_bytes())
+    }
+}
+
+impl ConfigOwned {
+    /// Write the config as cfg text, using the default FormatOptions
+    pub fn write_to<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+    {
+        self.write_with(writer, &FormatOptions::default())
+    }
+
+    /// Write the config as cfg text, using the supplied FormatOptions. See
+    /// Config::write_with.
+    pub fn write_with<W>(&self, writer: &mut W, opts: &FormatOptions) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+    {
+        let sections = owned_sections(self);
+        for (name, section) in sections.iter() {
+            validate(*name, *section, opts.names)?;
+        }
+        let mut out = String::new();
+        write_config(&mut out, &sections, opts).map_err(format_error)?;
+        writer.write_all(out.as_bytes())
+    }
+}
+
+impl Section<'_> {
+    /// Write the section, including its header, as cfg text, using the
+    /// default FormatOptions
+    pub fn write_to<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+    {
+        self.write_with(writer, &FormatOptions::default())
+    }
+
+    /// Write the section, including its header, as cfg text, using the
+    /// supplied FormatOptions. See Config::write_with.
+    pub fn write_with<W>(&self, writer: &mut W, opts: &FormatOptions) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+    {
+        validate(Some(self.name()), self, opts.names)?;
+        let mut out = String::new();
+        write_section(&mut out, Some(self.name()), self, opts).map_err(format_error)?;
+        writer.write_all(out.as_bytes())
+    }
+}
+
+impl SectionOwned {
+    /// Write the keys of the section as cfg text, using the default
+    /// FormatOptions. A SectionOwned does not know its name, so no header is
+    /// written.
+    pub fn write_to<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+    {
+        self.write_with(writer, &FormatOptions::default())
+    }
+
+    /// Write the keys of the section as cfg text, using the supplied
+    /// FormatOptions. See Config::write_with.
+    pub fn write_with<W>(&self, writer: &mut W, opts: &FormatOptions) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+    {
+        validate(None, self, opts.names)?;
+        let mut out = String::new();
+        write_section(&mut out, None, self, opts).map_err(format_error)?;
+        writer.write_all(out.as_bytes())
+    }
+}
+
+// the sections of a config, each paired with its name, or with None for the
+// implicit global section
+fn config_sections<'c>(config: &'c Config<'_>) -> Vec<(Option<&'c str>, &'c dyn Items)> {
+    let globals = config.globals().map(|section| section.name());
+    config
+        .sections()
+        .map(|section| {
+            let name = Some(section.name()).filter(|name| Some(*name) != globals);
+            (name, section as &dyn Items)
+        })
+        .collect()
+}
+
+fn owned_sections(config: &ConfigOwned) -> Vec<(Option<&str>, &dyn Items)> {
+    let globals = config.globals();
+    config
+        .iter()
+        .map(|(name, section)| {
+            let is_global = globals.is_some_and(|globals| std::ptr::eq(globals, section));
+            let name = Some(name.as_str()).filter(|_| !is_global);
+            (name, section as &dyn Items)
+        })
+        .collect()
+}
+
+// write the sections, starting with the global section, if there is one
+pub(crate) fn write_config(
+    out: &mut dyn Write,
+    sections: &[(Option<&str>, &dyn Items)],
+    opts: &FormatOptions,
+) -> fmt::Result {
+    let mut sections = sections.to_vec();
+    if opts.sort_sections {
+        sections.sort_by_key(|(name, _)| *name);
+    } else {
+        sections.sort_by_key(|(name, _)| name.is_some());
+    }
+    for (idx, (name, section)) in sections.into_iter().enumerate() {
+        if idx > 0 {
+            for _ in 0..opts.blank_lines {
+                writeln!(out)?;
+            }
+        }
+        write_section(out, name, section, opts)?;
+    }
+    Ok(())
+}
+
+// write a section, with a header unless it is unnamed
+fn write_section(
+    out: &mut dyn Write,
+    name: Option<&str>,
+    section: &dyn Items,
+    opts: &FormatOptions,
+) -> fmt::Result {
+    if let Some(name) = name {
+        let bang = if section.replaces() { "!" } else { "" };
+        match section.parent() {
+            Some(parent) => writeln!(out, "[{}{} : {}]", bang, name, parent)?,
+            None => writeln!(out, "[{}{}]", bang, name)?,
+        }
+    }
+    let mut items = section.items().collect::<Vec<_>>();
+    if opts.sort_keys {
+        items.sort();
+    }
+    for (key, value) in items {
+        write!(out, "{}{}", key, opts.delimiter)?;
+        write_value(out, value, opts.quoting)?;
+        writeln!(out)?;
+    }
+    for key in section.removed() {
+        writeln!(out, "!{}", key)?;
+    }
+    Ok(())
+}
+
+// write a value, quoting and escaping it if necessary
+pub(crate) fn write_value(out: &mut dyn Write, value: &str, quoting: Quoting) -> fmt::Result {
+    let needs_quotes =
+        value.is_empty() || value.contains(is_illegal) || value.contains(char::is_control);
+    if quoting == Quoting::WhenNeeded && !needs_quotes {
+        return out.write_str(value);
+    }
+    out.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\t' => out.write_str("\\t")?,
+            '\r' => out.write_str("\\r")?,
+            '\0' => out.write_str("\\0")?,
+            c if c.is_control() => write!(out, "\\u{{{:x}}}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+// could the character not be read back within an unquoted value. Besides
+// the characters the grammar rejects, a backslash may continue an unquoted
+// value onto the next line.
+fn is_illegal(c: char) -> bool {
+    ILLEGAL_CHARS.contains(&c) || c == '\\'
+}
+
+// check that the names within a section could be read back
+fn validate(name: Option<&str>, section: &dyn Items, allowed: Names) -> io::Result<()> {
+    let names = name
+        .into_iter()
+        .chain(section.parent())
+        .map(|n| (n, "section name"));
+    let keys = section
+        .items()
+        .map(|(key, _)| key)
+        .chain(section.removed())
+        .map(|key| (key, "key"));
+    for (text, what) in names.chain(keys) {
+        if !is_valid_name(text, allowed) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("'{}' is not a valid {}", text, what),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// could the grammar read the text back as a section name or key. A
+// permissive name must not hold brackets, delimiters or surrounding
+// whitespace, nor start like a comment or tombstone.
+pub(crate) fn is_valid_name(text: &str, names: Names) -> bool {
+    match names {
+        Names::Strict => matches!(alphaword_many0_underscore_word(text), Ok(("", _))),
+        Names::Permissive => {
+            !text.is_empty()
+                && text.trim() == text
+                && !text.starts_with(&['#', ';', '!'][..])
+                && !text.contains(&['[', ']', '=', ':'][..])
+                && !text.contains(char::is_control)
+        }
+    }
+}
+
+fn format_error(_: fmt::Error) -> io::Error {
+    io::Error::other("unable to format cfg")
+}
+
+#[cfg(test)]
+#[path = "./unit_tests/writer.rs"]
+mod unit_tests;